@@ -1,17 +1,710 @@
-use std::{env, process::exit};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Write},
+    path::PathBuf,
+    process::exit,
+};
+
+use chrono::{Local, NaiveDate};
+use ratatui::widgets::ListItem;
+use serde::Serialize;
+
+#[cfg(feature = "sqlite")]
+use crate::storage::sqlite::SqliteStorage;
+use crate::{
+    csv_file::CsvFile,
+    gpx::Gpx,
+    ics::Ics,
+    journal::{Journal, JournalFormat},
+    json::Json,
+    lock::Lock,
+    project::{DayOff, DayOffReason, Project, PROJECT_DATE_FORMAT},
+    report::Report,
+    stats::Stats,
+    task::{Status, Task, TASK_STATUS_DONE},
+    util::Util,
+    App,
+};
+
+static USAGE: &str = "Usage: basilk_pe [OPTIONS] [COMMAND]
+
+Without a command the TUI is started
+
+Commands:
+  add <exercise> [--date <date>]           Add an exercise to the day
+  log <exercise> <status> [--date <date>]  Set the status of an exercise (i.e. 75)
+  done <exercise> [--date <date>]          Mark an exercise as done
+  off <reason> [<note>] [--date <date>]    Mark the day as rest, sick or travel (none to clear it)
+  list [--date <date>]                     Print the exercises of the day
+  stats                                    Print the completion and the streaks
+  export <format> [<file>]                 Export the history (to the standard output by default),
+                                           the format is csv, markdown, org or ics
+  import csv <file>                        Merge the history of the file by day
+  import gpx <file>                        Add the recorded track to the day it started
+  migrate [--dry-run] [--rollback]         Migrate the json file to the last version
+  --backups                                Print the backups of the json file
+  --restore <backup>                       Restore a backup of the json file
+  --sqlite-import                          Copy the json file into the sqlite database
+  --sqlite-export                          Copy the sqlite database into the json file
+
+Options:
+  --date <date>      Day in the dd.mm.yyyy format, today by default
+  --format <format>  Output of list and stats: text (default) or json
+  --from <date>      First day of the export
+  --to <date>        Last day of the export
+  --data-dir <path>  Directory of the data (or the BASILK_PE_DIR environment variable)
+  --portable         Keep the data next to the binary
+  -h, --help         Print this help
+  --version          Print the version
+
+Exit codes: 0 success, 1 error, 2 wrong usage";
+
+const EXIT_ERROR: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+// Commands that work on the data without the TUI
+pub enum Command {
+    Add {
+        exercise: String,
+        date: String,
+    },
+    Log {
+        exercise: String,
+        status: Status,
+        date: String,
+    },
+    Off {
+        day_off: Option<DayOff>,
+        date: String,
+    },
+    List {
+        date: String,
+        format: Format,
+    },
+    Stats {
+        format: Format,
+    },
+    Export {
+        format: ExportFormat,
+        path: Option<String>,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    Import {
+        format: ImportFormat,
+        path: String,
+    },
+}
+
+// Files for the other applications (i.e. spreadsheets)
+#[derive(PartialEq, Clone, Copy)]
+pub enum ExportFormat {
+    Csv,
+    Journal(JournalFormat),
+    Ics,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ImportFormat {
+    Csv,
+    // A run or a ride recorded by a watch
+    Gpx,
+}
+
+// Output of the query commands, the json one follows the schema of `report.rs`
+#[derive(PartialEq, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+}
 
 pub struct Cli;
 
 impl Cli {
-    pub fn read() {
+    fn print_error(message: &str) -> i32 {
+        eprintln!("{} - ERROR: {message}", env!("CARGO_PKG_NAME"));
+        EXIT_ERROR
+    }
+
+    fn exit_with_error(message: &str) -> ! {
+        exit(Cli::print_error(message))
+    }
+
+    fn exit_with_usage_error(message: &str) -> ! {
+        eprintln!("{} - ERROR: {message}", env!("CARGO_PKG_NAME"));
+        eprintln!("See `{} --help`", env!("CARGO_PKG_NAME"));
+        exit(EXIT_USAGE)
+    }
+
+    // Commands that change the data can't run together with another session
+    fn lock_or_exit() -> File {
+        match Lock::acquire() {
+            Ok(Some(lock)) => lock,
+            Ok(None) => Cli::exit_with_error("Another session is running, close it and retry"),
+            Err(e) => Cli::exit_with_error(&e.to_string()),
+        }
+    }
+
+    // Remove the option and its value from the arguments
+    fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+        let index = args.iter().position(|a| a == name)?;
+        args.remove(index);
+
+        if index >= args.len() {
+            Cli::exit_with_usage_error(&format!("Missing the value of {name}"))
+        }
+
+        Some(args.remove(index))
+    }
+
+    // Remove the flag from the arguments, true if it was there
+    fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+        let found = args.iter().any(|a| a == name);
+        args.retain(|a| a != name);
+
+        found
+    }
+
+    fn parse_naive_date(date: &str) -> NaiveDate {
+        match NaiveDate::parse_from_str(date, PROJECT_DATE_FORMAT) {
+            Ok(date) => date,
+            Err(_) => Cli::exit_with_usage_error(&format!(
+                "Invalid date \"{date}\", the format is dd.mm.yyyy"
+            )),
+        }
+    }
+
+    fn parse_date(date: Option<String>) -> String {
+        match date {
+            // Normalize the date, i.e. 1.3.2026 => 01.03.2026
+            Some(date) => Cli::parse_naive_date(&date)
+                .format(PROJECT_DATE_FORMAT)
+                .to_string(),
+            None => Local::now().format(PROJECT_DATE_FORMAT).to_string(),
+        }
+    }
+
+    // Options that can be given with the command (the data directory ones work with all of them)
+    fn get_options_of(command: Option<&str>) -> &'static [&'static str] {
+        match command {
+            Some("add" | "done" | "log" | "off") => &["--date"],
+            Some("list") => &["--date", "--format"],
+            Some("stats") => &["--format"],
+            Some("export") => &["--from", "--to"],
+            _ => &[],
+        }
+    }
+
+    // Handle the one-shot arguments (and exit), returns the command to run on the data if any
+    pub fn read() -> Option<Command> {
         // If you use `cargo run main.rs`, skip must be 2
-        let mut args = env::args().skip(1);
+        let mut args: Vec<String> = env::args().skip(1).collect();
+
+        // Options of the data directory, they can be placed before or after the other arguments
+        if let Some(path) = Cli::take_option(&mut args, "--data-dir") {
+            Json::set_dir_path(PathBuf::from(path));
+        } else if Cli::take_flag(&mut args, "--portable") {
+            let Some(path) = Json::get_portable_dir_path() else {
+                Cli::exit_with_error("Unable to find the directory of the binary")
+            };
+
+            Json::set_dir_path(path);
+        }
+
+        let date = Cli::take_option(&mut args, "--date");
+        let from = Cli::take_option(&mut args, "--from").map(|d| Cli::parse_naive_date(&d));
+        let to = Cli::take_option(&mut args, "--to").map(|d| Cli::parse_naive_date(&d));
+
+        let format = Cli::take_option(&mut args, "--format");
 
-        if let Some(arg) = args.next() {
-            if arg == "--version" {
+        let command = args.first().map(String::as_str);
+        let options = [
+            ("--date", date.is_some()),
+            ("--from", from.is_some()),
+            ("--to", to.is_some()),
+            ("--format", format.is_some()),
+        ];
+
+        // An option is never ignored, i.e. `stats --date 01.03.2026` is a wrong usage
+        for (option, _) in options.iter().filter(|(_, given)| *given) {
+            if !Cli::get_options_of(command).contains(option) {
+                Cli::exit_with_usage_error(&match command {
+                    Some(command) => format!("{option} isn't an option of {command}"),
+                    None => format!("{option} needs a command"),
+                })
+            }
+        }
+
+        let format = match format.as_deref() {
+            None | Some("text") => Format::Text,
+            Some("json") => Format::Json,
+            Some(format) => Cli::exit_with_usage_error(&format!(
+                "Unknown format \"{format}\", use text or json"
+            )),
+        };
+
+        let mut args = args.into_iter();
+
+        let arg = args.next()?;
+
+        match arg.as_str() {
+            "--version" => {
                 print!(env!("CARGO_PKG_VERSION"));
                 exit(0)
             }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                exit(0)
+            }
+            "--backups" => {
+                for backup in Json::get_backups() {
+                    println!("{backup}");
+                }
+                exit(0)
+            }
+            #[cfg(feature = "sqlite")]
+            "--sqlite-import" | "--sqlite-export" => {
+                let _lock = Cli::lock_or_exit();
+
+                let result = if arg == "--sqlite-import" {
+                    SqliteStorage::import_json()
+                } else {
+                    SqliteStorage::export_json()
+                };
+
+                match result {
+                    Ok(count) => {
+                        println!("{count} days copied");
+                        exit(0)
+                    }
+                    Err(e) => Cli::exit_with_error(&e.to_string()),
+                }
+            }
+            "migrate" => {
+                let options: Vec<String> = args.collect();
+
+                // A mistyped option (i.e. --dryrun) would run the real migration
+                if let Some(option) = options
+                    .iter()
+                    .find(|o| !["--dry-run", "--rollback"].contains(&o.as_str()))
+                {
+                    Cli::exit_with_usage_error(&format!("{option} isn't an option of migrate"))
+                }
+
+                let dry_run = options.iter().any(|o| o == "--dry-run");
+                let rollback = options.iter().any(|o| o == "--rollback");
+
+                // The dry run only reads the json file
+                let _lock = if dry_run {
+                    None
+                } else {
+                    Some(Cli::lock_or_exit())
+                };
+
+                match Json::migrate(rollback, dry_run) {
+                    Ok(lines) if lines.is_empty() => {
+                        println!("Nothing to migrate");
+                        exit(0)
+                    }
+                    Ok(lines) => {
+                        for line in lines {
+                            println!("{line}");
+                        }
+
+                        if dry_run {
+                            println!("Dry run, nothing was changed");
+                        } else if rollback {
+                            // Any start of this version migrates the file again
+                            eprintln!(
+                                "{} - WARNING: Use an older version of {} with this file, this one migrates it again at the next start",
+                                env!("CARGO_PKG_NAME"),
+                                env!("CARGO_PKG_NAME")
+                            );
+                        }
+                        exit(0)
+                    }
+                    Err(e) => Cli::exit_with_error(&e.to_string()),
+                }
+            }
+            "--restore" => {
+                let Some(name) = args.next() else {
+                    Cli::exit_with_usage_error("Missing the backup name, see --backups")
+                };
+
+                let _lock = Cli::lock_or_exit();
+
+                match Json::restore_backup(&name) {
+                    Ok(_) => {
+                        println!("Backup \"{name}\" restored");
+                        exit(0)
+                    }
+                    Err(e) => Cli::exit_with_error(&e.to_string()),
+                }
+            }
+            "add" | "done" => {
+                let Some(exercise) = args.next() else {
+                    Cli::exit_with_usage_error(&format!("Missing the exercise of {arg}"))
+                };
+
+                if arg == "add" {
+                    Some(Command::Add {
+                        exercise,
+                        date: Cli::parse_date(date),
+                    })
+                } else {
+                    Some(Command::Log {
+                        exercise,
+                        status: TASK_STATUS_DONE,
+                        date: Cli::parse_date(date),
+                    })
+                }
+            }
+            "log" => {
+                let (Some(exercise), Some(status)) = (args.next(), args.next()) else {
+                    Cli::exit_with_usage_error("The usage is: log <exercise> <status>")
+                };
+
+                let Ok(status) = status.trim_end_matches('%').parse::<u8>() else {
+                    Cli::exit_with_usage_error(&format!("Invalid status \"{status}\""))
+                };
+
+                Some(Command::Log {
+                    exercise,
+                    status: Status::new(status),
+                    date: Cli::parse_date(date),
+                })
+            }
+            "off" => {
+                let Some(reason) = args.next() else {
+                    Cli::exit_with_usage_error("The usage is: off <reason> [<note>]")
+                };
+
+                let day_off = if reason == "none" {
+                    None
+                } else {
+                    let Some(reason) = DayOffReason::parse(&reason) else {
+                        Cli::exit_with_usage_error(&format!(
+                            "Invalid reason \"{reason}\", expected rest, sick, travel or none"
+                        ))
+                    };
+
+                    Some(DayOff {
+                        reason,
+                        note: args.next().unwrap_or_default().trim().to_string(),
+                    })
+                };
+
+                Some(Command::Off {
+                    day_off,
+                    date: Cli::parse_date(date),
+                })
+            }
+            "list" => Some(Command::List {
+                date: Cli::parse_date(date),
+                format,
+            }),
+            "stats" => Some(Command::Stats { format }),
+            "export" => {
+                let format = match args.next().as_deref() {
+                    Some("csv") => ExportFormat::Csv,
+                    Some("markdown" | "md") => ExportFormat::Journal(JournalFormat::Markdown),
+                    Some("org") => ExportFormat::Journal(JournalFormat::Org),
+                    Some("ics") => ExportFormat::Ics,
+                    Some(format) => {
+                        Cli::exit_with_usage_error(&format!("Unknown export format \"{format}\""))
+                    }
+                    None => Cli::exit_with_usage_error("Missing the export format"),
+                };
+
+                Some(Command::Export {
+                    format,
+                    path: args.next(),
+                    from,
+                    to,
+                })
+            }
+            "import" => {
+                let format = match args.next().as_deref() {
+                    Some("csv") => ImportFormat::Csv,
+                    Some("gpx") => ImportFormat::Gpx,
+                    Some(format) => {
+                        Cli::exit_with_usage_error(&format!("Unknown import format \"{format}\""))
+                    }
+                    None => Cli::exit_with_usage_error("Missing the import format"),
+                };
+
+                let Some(path) = args.next() else {
+                    Cli::exit_with_usage_error("Missing the file to import")
+                };
+
+                Some(Command::Import { format, path })
+            }
+            _ => Cli::exit_with_usage_error(&format!("Unknown command \"{arg}\"")),
         }
     }
+
+    // Select the project of the date, it is created (like in the TUI) if missing
+    fn select_project(app: &mut App, items: &mut Vec<ListItem>, date: &str) {
+        if !app.projects.iter().any(|p| p.title == date) {
+            Project::create(app, items, date.to_string());
+        }
+
+        let index = app.projects.iter().position(|p| p.title == date);
+        app.selected_project_index.select(index);
+    }
+
+    // The reader may stop early (i.e. `| head`), it is not an error
+    fn print_json(value: &impl Serialize) {
+        let mut stdout = io::stdout().lock();
+
+        if serde_json::to_writer_pretty(&mut stdout, value).is_ok() {
+            let _ = writeln!(stdout);
+        }
+    }
+
+    // Run the command on the data, returns the exit code
+    pub fn run(app: &mut App, command: Command) -> i32 {
+        let is_query = matches!(
+            command,
+            Command::List { .. } | Command::Stats { .. } | Command::Export { .. }
+        );
+
+        if app.read_only && !is_query {
+            return Cli::print_error("Another session is running, close it and retry");
+        }
+
+        let mut items: Vec<ListItem> = vec![];
+
+        match command {
+            Command::List { date, format } => {
+                let project = app.projects.iter().find(|p| p.title == date);
+
+                if format == Format::Json {
+                    let report = Report::get_days(&project.into_iter().collect::<Vec<_>>());
+                    Cli::print_json(&report);
+                    return 0;
+                }
+
+                let Some(project) = project else {
+                    println!("No exercises on {date}");
+                    return 0;
+                };
+
+                println!("{}", project.title);
+
+                if let Some(day_off) = &project.day_off {
+                    println!("  day off: {}", day_off.get_repr());
+                }
+
+                for task in project.tasks.iter() {
+                    let mut line = format!("  [{}] ", task.status);
+
+                    if task.priority != 0 {
+                        line.push_str(&format!(
+                            "[{}] ",
+                            Util::get_priority_indicator(task.priority)
+                        ));
+                    }
+
+                    line.push_str(&task.title);
+
+                    if let Some(stats) = Task::get_stats_repr(task) {
+                        line.push_str(&format!("  {stats}"));
+                    }
+
+                    println!("{line}");
+                }
+            }
+            Command::Stats { format } => {
+                let streaks = Stats::get_streaks(
+                    &app.projects,
+                    app.config.streak.threshold as f32,
+                    Local::now().date_naive(),
+                );
+
+                if format == Format::Json {
+                    let report = Report::get_stats(&app.projects, streaks);
+                    Cli::print_json(&report);
+                    return 0;
+                }
+
+                println!("Days: {}", app.projects.len());
+
+                if let Some(completion) = Stats::get_average_completion(&app.projects) {
+                    println!("Completion: {completion:.0}%");
+                }
+
+                println!("Current streak: {} days", streaks.current);
+                println!("Longest streak: {} days", streaks.longest);
+            }
+            Command::Export {
+                format,
+                path,
+                from,
+                to,
+            } => {
+                let writer: Box<dyn Write> = match &path {
+                    Some(path) => match File::create(path) {
+                        Ok(file) => Box::new(file),
+                        Err(e) => return Cli::print_error(&format!("{path}: {e}")),
+                    },
+                    None => Box::new(io::stdout()),
+                };
+
+                // A date range leaves out the days that are not titled by a date
+                let projects: Vec<Project> = if from.is_none() && to.is_none() {
+                    app.projects.clone()
+                } else {
+                    app.projects
+                        .iter()
+                        .filter(|p| {
+                            NaiveDate::parse_from_str(&p.title, PROJECT_DATE_FORMAT).is_ok_and(
+                                |d| from.is_none_or(|f| d >= f) && to.is_none_or(|t| d <= t),
+                            )
+                        })
+                        .cloned()
+                        .collect()
+                };
+
+                let result = match format {
+                    ExportFormat::Csv => CsvFile::export(&projects, writer),
+                    ExportFormat::Journal(format) => {
+                        Journal::export(&projects, format, writer).map_err(|e| e.into())
+                    }
+                    ExportFormat::Ics => Ics::export(&projects, Local::now().date_naive(), writer)
+                        .map_err(|e| e.into()),
+                };
+
+                if let Err(e) = result {
+                    return Cli::print_error(&e.to_string());
+                }
+            }
+            Command::Import {
+                format: ImportFormat::Csv,
+                path,
+            } => {
+                let file = match File::open(&path) {
+                    Ok(file) => file,
+                    Err(e) => return Cli::print_error(&format!("{path}: {e}")),
+                };
+
+                let result = match CsvFile::import(app.projects.clone(), file) {
+                    Ok(result) => result,
+                    Err(e) => return Cli::print_error(&format!("{path}: {e}")),
+                };
+
+                for duplicate in result.duplicates.iter() {
+                    eprintln!("Skipped, {duplicate}");
+                }
+
+                app.storage.write(result.projects);
+                app.projects = app.storage.read();
+
+                println!(
+                    "{} exercises imported, {} duplicates skipped",
+                    result.imported,
+                    result.duplicates.len()
+                );
+            }
+            Command::Import {
+                format: ImportFormat::Gpx,
+                path,
+            } => {
+                let gpx_track = match fs::read_to_string(&path)
+                    .map_err(|e| e.into())
+                    .and_then(|text| Gpx::parse(&text))
+                {
+                    Ok(gpx_track) => gpx_track,
+                    Err(e) => return Cli::print_error(&format!("{path}: {e}")),
+                };
+
+                let date = Gpx::get_date(&gpx_track)
+                    .format(PROJECT_DATE_FORMAT)
+                    .to_string();
+
+                Cli::select_project(app, &mut items, &date);
+
+                let mut internal_projects = app.projects.clone();
+                let project =
+                    &mut internal_projects[app.selected_project_index.selected().unwrap()];
+
+                let title = match Gpx::add_track(project, gpx_track) {
+                    Ok(title) => title,
+                    Err(e) => return Cli::print_error(&format!("{path}: {e}")),
+                };
+
+                let stats = Task::get_stats_repr(project.tasks.last().unwrap()).unwrap_or_default();
+
+                app.storage.write(internal_projects);
+                app.projects = app.storage.read();
+
+                println!("Added \"{title}\" on {date}: {stats}");
+            }
+            Command::Off { day_off, date } => {
+                Cli::select_project(app, &mut items, &date);
+
+                let mut internal_projects = app.projects.clone();
+                internal_projects[app.selected_project_index.selected().unwrap()].day_off =
+                    day_off.clone();
+
+                app.storage.write(internal_projects);
+                app.projects = app.storage.read();
+
+                match day_off {
+                    Some(day_off) => println!("{date} marked as {}", day_off.get_repr()),
+                    None => println!("{date} is a training day"),
+                }
+            }
+            Command::Add { exercise, date } => {
+                if exercise.trim().is_empty() {
+                    eprintln!("{} - ERROR: The exercise is empty", env!("CARGO_PKG_NAME"));
+                    return EXIT_USAGE;
+                }
+
+                Cli::select_project(app, &mut items, &date);
+                Task::create(app, &mut items, &exercise);
+
+                println!("Added \"{exercise}\" on {date}");
+            }
+            Command::Log {
+                exercise,
+                status,
+                date,
+            } => {
+                let statuses = app.config.status.scale.get_statuses();
+
+                if !statuses.contains(&status) {
+                    let statuses: Vec<String> = statuses.iter().map(|s| s.to_string()).collect();
+
+                    eprintln!(
+                        "{} - ERROR: The status must be one of {}",
+                        env!("CARGO_PKG_NAME"),
+                        statuses.join(", ")
+                    );
+                    return EXIT_USAGE;
+                }
+
+                Cli::select_project(app, &mut items, &date);
+
+                let tasks = &app.projects[app.selected_project_index.selected().unwrap()].tasks;
+
+                let Some(index) = tasks
+                    .iter()
+                    .position(|t| t.title.eq_ignore_ascii_case(&exercise))
+                else {
+                    return Cli::print_error(&format!(
+                        "No exercise \"{exercise}\" on {date}, use `add` to create it"
+                    ));
+                };
+
+                app.selected_task_index.select(Some(index));
+                Task::change_status(app, &mut items, status);
+
+                println!("\"{exercise}\" on {date}: {status}");
+            }
+        }
+
+        0
+    }
 }
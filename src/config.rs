@@ -1,17 +1,45 @@
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fs::{self, File},
     io::Write,
     path::PathBuf,
     process::exit,
 };
 
+use chrono::{Datelike, NaiveDate, Weekday};
+use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
-use crate::json::Json;
+use crate::{
+    json::Json,
+    keys::{Action, Key, Keys},
+    project::PROJECT_DATE_FORMAT,
+    task::{StatusScale, TaskKind, TASK_ITEMS_PE},
+};
 
 #[derive(Deserialize, Serialize)]
 pub struct ConfigToml {
     pub ui: Ui,
+    // Optional in order to keep valid the config files created before this section
+    #[serde(default)]
+    pub template: Template,
+    // Named exercise lists referenced by the schedule (i.e. legs, push, pull)
+    #[serde(default)]
+    pub routines: BTreeMap<String, Vec<TemplateExercise>>,
+    #[serde(default)]
+    pub schedule: Schedule,
+    #[serde(default)]
+    pub backup: Backup,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub status: StatusConfig,
+    #[serde(default)]
+    pub streak: StreakConfig,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub keys: KeysConfig,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -20,17 +48,310 @@ pub struct Ui {
     pub show_grid_activity: bool,
 }
 
+// Exercises used to fill every new day
+#[derive(Deserialize, Serialize)]
+pub struct Template {
+    pub exercises: Vec<TemplateExercise>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct TemplateExercise {
+    pub title: String,
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub kind: TaskKind,
+    // Reps, seconds or kilometres depending on the kind
+    #[serde(default, alias = "target_reps")]
+    pub target: f32,
+    // Groups of exercises for the grid activity filter (i.e. ["legs"])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Template {
+            exercises: TASK_ITEMS_PE
+                .iter()
+                .map(|&item| TemplateExercise {
+                    title: item.to_string(),
+                    priority: 0,
+                    kind: TaskKind::Reps,
+                    target: 0.0,
+                    tags: vec![],
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Backup {
+    // How many backups of the json file are kept, the oldest ones are removed
+    pub keep: usize,
+}
+
+impl Default for Backup {
+    fn default() -> Self {
+        Backup { keep: 10 }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Json,
+    // Requires the "sqlite" cargo feature
+    Sqlite,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct StorageConfig {
+    pub backend: Backend,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct StatusConfig {
+    pub scale: StatusScale,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct StreakConfig {
+    // Minimum completion (mean of the statuses) of a day to extend the streak
+    pub threshold: u8,
+}
+
+impl Default for StreakConfig {
+    fn default() -> Self {
+        StreakConfig { threshold: 100 }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+// Built-in theme and the colours replacing its ones (i.e. "red", "#39d353" or "8")
+#[derive(Deserialize, Serialize, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub name: ThemeName,
+    // Status 0, under 50, under 75, under 100 and done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<[Color; 5]>,
+    // Done tasks of a day: none, from a quarter, from a half, all of them and the others
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_tasks: Option<[Color; 5]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted: Option<Color>,
+    // Grid activity, from no activity to every exercise done
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heatmap: Option<[Color; 5]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_off: Option<Color>,
+}
+
+// Keys replacing the default ones of an action (i.e. delete = ["x"]), for each view
+#[derive(Deserialize, Serialize, Default)]
+pub struct KeysConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<Action, Vec<Key>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tasks: BTreeMap<Action, Vec<Key>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub grid: BTreeMap<Action, Vec<Key>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub delete: BTreeMap<Action, Vec<Key>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conflict: BTreeMap<Action, Vec<Key>>,
+}
+
+// Routine name reserved for the days without exercises
+pub const REST_ROUTINE: &str = "rest";
+
+#[derive(Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleMode {
+    // Every day gets the exercises of the template
+    #[default]
+    Template,
+    // Each weekday gets its own routine, the missing weekdays are rest days
+    Weekday,
+    // The routines are cycled in order, each one lasts `every` days
+    Rotation,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Schedule {
+    #[serde(default)]
+    pub mode: ScheduleMode,
+    // Weekday (i.e. "monday" or "mon") => routine name
+    #[serde(default)]
+    pub weekdays: BTreeMap<String, String>,
+    #[serde(default)]
+    pub rotation: Vec<String>,
+    #[serde(default = "Schedule::get_default_every")]
+    pub every: u32,
+    // Date (dd.mm.yyyy) when the rotation starts from the first routine, 01.01.1970 without it
+    #[serde(default)]
+    pub start: Option<String>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            mode: ScheduleMode::default(),
+            weekdays: BTreeMap::new(),
+            rotation: vec![],
+            every: Schedule::get_default_every(),
+            start: None,
+        }
+    }
+}
+
+impl Schedule {
+    fn get_default_every() -> u32 {
+        1
+    }
+
+    // A start that is not a date would silently move the rotation, and an unknown weekday
+    // or routine would silently become a rest day or the template
+    pub fn validate(
+        &self,
+        routines: &BTreeMap<String, Vec<TemplateExercise>>,
+    ) -> Result<(), String> {
+        if let Some(start) = &self.start {
+            if NaiveDate::parse_from_str(start, PROJECT_DATE_FORMAT).is_err() {
+                return Err(format!(
+                    "the schedule start \"{start}\" is not a dd.mm.yyyy date"
+                ));
+            }
+        }
+
+        if let Some(day) = self.weekdays.keys().find(|d| d.parse::<Weekday>().is_err()) {
+            return Err(format!("the schedule weekday \"{day}\" is unknown"));
+        }
+
+        let names = self.weekdays.values().chain(self.rotation.iter());
+
+        for name in names {
+            if name != REST_ROUTINE && !routines.contains_key(name) {
+                return Err(format!(
+                    "the schedule routine \"{name}\" is missing from [routines]"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Name of the routine planned for the date, None means "use the template"
+    fn get_routine_name(&self, date: NaiveDate) -> Option<&str> {
+        match self.mode {
+            ScheduleMode::Template => None,
+            ScheduleMode::Weekday => Some(
+                self.weekdays
+                    .iter()
+                    .find(|(day, _)| day.parse::<Weekday>().ok() == Some(date.weekday()))
+                    .map(|(_, routine)| routine.as_str())
+                    .unwrap_or(REST_ROUTINE),
+            ),
+            ScheduleMode::Rotation => {
+                if self.rotation.is_empty() {
+                    return None;
+                }
+
+                // The start is checked when the config is read, the default is 01.01.1970
+                let start = self
+                    .start
+                    .as_ref()
+                    .and_then(|s| NaiveDate::parse_from_str(s, PROJECT_DATE_FORMAT).ok())
+                    .unwrap_or_default();
+
+                let days = (date - start).num_days();
+                let step = days.div_euclid(self.every.max(1) as i64);
+                let index = step.rem_euclid(self.rotation.len() as i64) as usize;
+
+                Some(self.rotation[index].as_str())
+            }
+        }
+    }
+}
+
+impl ConfigToml {
+    // Exercises to create for the date following the schedule
+    pub fn get_exercises_for(&self, date: NaiveDate) -> &[TemplateExercise] {
+        match self.schedule.get_routine_name(date) {
+            Some(REST_ROUTINE) => &[],
+            // The routines are checked when the config is read, else the template is used
+            Some(name) => self.routines.get(name).unwrap_or(&self.template.exercises),
+            None => &self.template.exercises,
+        }
+    }
+
+    // Tag => titles of its exercises. The exercises of a routine are tagged with its name
+    pub fn get_tags(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut tags: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        let exercises = self
+            .template
+            .exercises
+            .iter()
+            .chain(self.routines.values().flatten());
+
+        for exercise in exercises {
+            for tag in exercise.tags.iter() {
+                tags.entry(tag.clone())
+                    .or_default()
+                    .insert(exercise.title.clone());
+            }
+        }
+
+        for (name, exercises) in self.routines.iter() {
+            if name == REST_ROUTINE {
+                continue;
+            }
+
+            tags.entry(name.clone())
+                .or_default()
+                .extend(exercises.iter().map(|e| e.title.clone()));
+        }
+
+        tags.retain(|_, titles| !titles.is_empty());
+
+        tags
+    }
+}
+
 pub struct Config;
 
 static CONFIG_FILE_NAME: &str = "config";
 
 impl Config {
-    fn get_default() -> ConfigToml {
+    pub fn get_default() -> ConfigToml {
         ConfigToml {
             ui: Ui {
                 show_help: true,
                 show_grid_activity: true,
             },
+            template: Template::default(),
+            routines: BTreeMap::new(),
+            schedule: Schedule::default(),
+            backup: Backup::default(),
+            storage: StorageConfig::default(),
+            status: StatusConfig::default(),
+            streak: StreakConfig::default(),
+            theme: ThemeConfig::default(),
+            keys: KeysConfig::default(),
         }
     }
 
@@ -69,6 +390,147 @@ impl Config {
             }
         };
 
+        if let Err(error) = data
+            .schedule
+            .validate(&data.routines)
+            .and_then(|_| Keys::new(&data.keys).validate())
+        {
+            eprint!(
+                "{} - ERROR: The configuration file is invalid, {error}",
+                env!("CARGO_PKG_NAME")
+            );
+            exit(1)
+        }
+
         data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_date(day: u32, month: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn get_titles(config: &ConfigToml, date: NaiveDate) -> Vec<&str> {
+        config
+            .get_exercises_for(date)
+            .iter()
+            .map(|e| e.title.as_str())
+            .collect()
+    }
+
+    fn get_config(toml: &str) -> ConfigToml {
+        let mut config = Config::get_default();
+        let parsed: ConfigToml = toml::from_str(&format!(
+            "[ui]\nshow_help = true\nshow_grid_activity = true\n{toml}"
+        ))
+        .unwrap();
+
+        config.schedule = parsed.schedule;
+        config.routines = parsed.routines;
+
+        config
+    }
+
+    #[test]
+    fn weekday_schedule_uses_the_routine_of_the_day() {
+        let config = get_config(
+            r#"
+            [schedule]
+            mode = "weekday"
+            weekdays = { mon = "legs", wednesday = "push" }
+
+            [[routines.legs]]
+            title = "squats"
+
+            [[routines.push]]
+            title = "bench"
+            "#,
+        );
+
+        // 12.10.2026 is a monday
+        assert_eq!(get_titles(&config, get_date(12, 10)), ["squats"]);
+        assert_eq!(get_titles(&config, get_date(14, 10)), ["bench"]);
+        // Missing weekday
+        assert!(get_titles(&config, get_date(13, 10)).is_empty());
+
+        assert!(config.schedule.validate(&config.routines).is_ok());
+    }
+
+    #[test]
+    fn rotation_schedule_cycles_the_routines() {
+        let config = get_config(
+            r#"
+            [schedule]
+            mode = "rotation"
+            rotation = ["legs", "rest", "push"]
+            every = 2
+            start = "10.10.2026"
+
+            [[routines.legs]]
+            title = "squats"
+
+            [[routines.push]]
+            title = "bench"
+            "#,
+        );
+
+        assert_eq!(get_titles(&config, get_date(10, 10)), ["squats"]);
+        assert_eq!(get_titles(&config, get_date(11, 10)), ["squats"]);
+        assert!(get_titles(&config, get_date(12, 10)).is_empty());
+        assert_eq!(get_titles(&config, get_date(15, 10)), ["bench"]);
+        assert_eq!(get_titles(&config, get_date(16, 10)), ["squats"]);
+        // Before the start the rotation goes backwards
+        assert_eq!(get_titles(&config, get_date(9, 10)), ["bench"]);
+        assert!(get_titles(&config, get_date(7, 10)).is_empty());
+
+        assert!(config.schedule.validate(&config.routines).is_ok());
+    }
+
+    #[test]
+    fn schedule_start_must_be_a_date() {
+        let config = get_config(
+            r#"
+            [schedule]
+            mode = "rotation"
+            rotation = ["legs"]
+            start = "2026-10-10"
+            "#,
+        );
+
+        assert!(config.schedule.validate(&config.routines).is_err());
+
+        let config = get_config("");
+        assert!(config.schedule.validate(&config.routines).is_ok());
+    }
+
+    #[test]
+    fn schedule_weekdays_and_routines_must_exist() {
+        let get_error = |toml: &str| {
+            let config = get_config(toml);
+            config.schedule.validate(&config.routines).err()
+        };
+
+        assert_eq!(
+            get_error("[schedule]\nweekdays = { tues = \"rest\" }"),
+            Some("the schedule weekday \"tues\" is unknown".to_string())
+        );
+        assert_eq!(
+            get_error("[schedule]\nweekdays = { \"Tuesday \" = \"rest\" }"),
+            Some("the schedule weekday \"Tuesday \" is unknown".to_string())
+        );
+        assert_eq!(
+            get_error("[schedule]\nrotation = [\"legs\", \"rest\"]"),
+            Some("the schedule routine \"legs\" is missing from [routines]".to_string())
+        );
+        assert_eq!(
+            get_error(
+                "[schedule]\nweekdays = { Tuesday = \"legs\", sun = \"rest\" }\n[[routines.legs]]\ntitle = \"squats\""
+            ),
+            None
+        );
+    }
+}
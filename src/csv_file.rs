@@ -0,0 +1,350 @@
+use std::{error::Error, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    project::{DayOff, DayOffReason, Project},
+    task::{Status, Task, TaskKind, Track, TASK_PRIORITIES},
+};
+
+// One exercise of one day. A day without exercises has a single row with an empty exercise,
+// so it is kept by an export/import round trip
+#[derive(Serialize, Deserialize, Debug)]
+struct CsvRow {
+    // Title of the day (i.e. "18.10.2026")
+    date: String,
+    exercise: String,
+    status: Option<u8>,
+    priority: Option<u8>,
+    // Reps, seconds or kilometres depending on the kind
+    #[serde(alias = "target_reps")]
+    target: Option<f32>,
+    // Reps of each set separated by spaces (i.e. "10 12 8")
+    sets: String,
+    weight: Option<f32>,
+    // The next columns are missing in the files exported before the task kinds
+    #[serde(default)]
+    kind: TaskKind,
+    // Seconds or kilometres done, empty for the reps
+    #[serde(default)]
+    progress: Option<f32>,
+    // Recorded session, the columns are empty without it
+    #[serde(default)]
+    track_start: String,
+    #[serde(default)]
+    distance_km: Option<f64>,
+    #[serde(default)]
+    duration_s: Option<u64>,
+    #[serde(default)]
+    elevation_gain_m: Option<f64>,
+    // Reason and note of the day off, repeated on each row of the day
+    #[serde(default)]
+    day_off: Option<DayOffReason>,
+    #[serde(default)]
+    day_off_note: String,
+}
+
+// Result of the merge of a csv file into the projects
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    pub projects: Vec<Project>,
+    pub imported: usize,
+    // i.e. "line 4: pushups already exists on 18.10.2026"
+    pub duplicates: Vec<String>,
+}
+
+pub struct CsvFile;
+
+impl CsvFile {
+    pub fn export(projects: &[Project], writer: impl io::Write) -> Result<(), Box<dyn Error>> {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        for project in projects {
+            let day_off = project.day_off.as_ref().map(|d| d.reason);
+            let day_off_note = project
+                .day_off
+                .as_ref()
+                .map_or("".to_string(), |d| d.note.clone());
+
+            if project.tasks.is_empty() {
+                writer.serialize(CsvRow {
+                    date: project.title.clone(),
+                    exercise: "".to_string(),
+                    status: None,
+                    priority: None,
+                    target: None,
+                    sets: "".to_string(),
+                    weight: None,
+                    kind: TaskKind::Reps,
+                    progress: None,
+                    track_start: "".to_string(),
+                    distance_km: None,
+                    duration_s: None,
+                    elevation_gain_m: None,
+                    day_off,
+                    day_off_note: day_off_note.clone(),
+                })?;
+            }
+
+            for task in project.tasks.iter() {
+                let sets: Vec<String> = task.sets.iter().map(|s| s.to_string()).collect();
+
+                writer.serialize(CsvRow {
+                    date: project.title.clone(),
+                    exercise: task.title.clone(),
+                    status: Some(task.status.percentage()),
+                    priority: Some(task.priority),
+                    target: Some(task.target),
+                    sets: sets.join(" "),
+                    weight: Some(task.weight),
+                    kind: task.kind,
+                    progress: (task.kind != TaskKind::Reps).then_some(task.progress),
+                    track_start: task
+                        .track
+                        .as_ref()
+                        .map_or("".to_string(), |t| t.start.clone()),
+                    distance_km: task.track.as_ref().map(|t| t.distance_km),
+                    duration_s: task.track.as_ref().map(|t| t.duration_s),
+                    elevation_gain_m: task.track.as_ref().map(|t| t.elevation_gain_m),
+                    day_off,
+                    day_off_note: day_off_note.clone(),
+                })?;
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    // Merge the rows into the projects by day title. The exercises that already exist
+    // on the day are not changed and they are reported as duplicates.
+    // Nothing is merged if a row is invalid
+    pub fn import(
+        projects: Vec<Project>,
+        reader: impl io::Read,
+    ) -> Result<CsvImport, Box<dyn Error>> {
+        let mut reader = csv::Reader::from_reader(reader);
+
+        let mut result = CsvImport {
+            projects,
+            ..Default::default()
+        };
+
+        for (index, row) in reader.deserialize::<CsvRow>().enumerate() {
+            let row = row?;
+            // The header is the line 1
+            let line = index + 2;
+
+            if row.date.trim().is_empty() {
+                return Err(format!("line {line}: the date is empty").into());
+            }
+
+            let priority = row.priority.unwrap_or(0);
+
+            if !TASK_PRIORITIES.contains(&priority) {
+                return Err(format!("line {line}: invalid priority {priority}").into());
+            }
+
+            // Like in the editor of the task
+            for (name, value) in [
+                ("weight", row.weight),
+                ("target", row.target),
+                ("progress", row.progress),
+            ] {
+                if let Some(value) = value.filter(|v| !Task::is_valid_number(*v)) {
+                    return Err(format!("line {line}: invalid {name} {value}").into());
+                }
+            }
+
+            let sets = row
+                .sets
+                .split_whitespace()
+                .map(|s| s.parse())
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(|_| format!("line {line}: invalid sets \"{}\"", row.sets))?;
+
+            let index = match result.projects.iter().position(|p| p.title == row.date) {
+                Some(index) => index,
+                None => {
+                    result.projects.push(Project {
+                        title: row.date.clone(),
+                        tasks: vec![],
+                        day_off: None,
+                    });
+                    result.projects.len() - 1
+                }
+            };
+
+            // An existing day off is kept
+            if let Some(reason) = row.day_off {
+                result.projects[index].day_off.get_or_insert(DayOff {
+                    reason,
+                    note: row.day_off_note.trim().to_string(),
+                });
+            }
+
+            if row.exercise.is_empty() {
+                continue;
+            }
+
+            let tasks = &mut result.projects[index].tasks;
+
+            // The titles are matched like in the command line (i.e. "Pushups" is "pushups")
+            if tasks
+                .iter()
+                .any(|t| t.title.eq_ignore_ascii_case(&row.exercise))
+            {
+                result.duplicates.push(format!(
+                    "line {line}: {} already exists on {}",
+                    row.exercise, row.date
+                ));
+                continue;
+            }
+
+            let track = if row.track_start.is_empty() {
+                None
+            } else {
+                Some(Track {
+                    start: row.track_start,
+                    distance_km: row.distance_km.unwrap_or(0.0),
+                    duration_s: row.duration_s.unwrap_or(0),
+                    elevation_gain_m: row.elevation_gain_m.unwrap_or(0.0),
+                })
+            };
+
+            tasks.push(Task {
+                title: row.exercise,
+                status: Status::new(row.status.unwrap_or(0)),
+                priority,
+                target: row.target.unwrap_or(0.0),
+                progress: row.progress.unwrap_or(0.0),
+                sets,
+                weight: row.weight.unwrap_or(0.0),
+                kind: row.kind,
+                track,
+            });
+            result.imported += 1;
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::TASK_STATUS_DONE;
+
+    fn get_projects() -> Vec<Project> {
+        vec![
+            Project {
+                title: "01.10.2026".to_string(),
+                tasks: vec![
+                    Task {
+                        title: "pushups".to_string(),
+                        status: TASK_STATUS_DONE,
+                        priority: 1,
+                        target: 36.0,
+                        progress: 0.0,
+                        sets: vec![10, 12, 14],
+                        weight: 20.5,
+                        kind: TaskKind::Reps,
+                        track: None,
+                    },
+                    Task {
+                        title: "run".to_string(),
+                        status: TASK_STATUS_DONE,
+                        priority: 0,
+                        target: 0.0,
+                        progress: 5.2,
+                        sets: vec![],
+                        weight: 0.0,
+                        kind: TaskKind::Distance,
+                        track: Some(Track {
+                            start: "2026-10-01T07:00:00+00:00".to_string(),
+                            distance_km: 5.2,
+                            duration_s: 1694,
+                            elevation_gain_m: 45.0,
+                        }),
+                    },
+                ],
+                day_off: None,
+            },
+            Project {
+                title: "02.10.2026".to_string(),
+                tasks: vec![],
+                day_off: Some(DayOff {
+                    reason: DayOffReason::Travel,
+                    note: "flight, no gym".to_string(),
+                }),
+            },
+        ]
+    }
+
+    #[test]
+    fn export_then_import_gives_the_same_projects() {
+        let mut csv = vec![];
+        CsvFile::export(&get_projects(), &mut csv).unwrap();
+
+        let result = CsvFile::import(vec![], csv.as_slice()).unwrap();
+
+        assert_eq!(result.imported, 2);
+        assert!(result.duplicates.is_empty());
+        assert_eq!(
+            serde_json::to_value(&result.projects).unwrap(),
+            serde_json::to_value(get_projects()).unwrap()
+        );
+    }
+
+    #[test]
+    fn import_merges_by_date_and_reports_the_duplicates() {
+        // Exported before the task kinds
+        let csv = "date,exercise,status,priority,target_reps,sets,weight
+01.10.2026,Pushups,50,0,,,
+01.10.2026,squats,25,0,,,
+03.10.2026,plank,,,,,
+";
+
+        let result = CsvFile::import(get_projects(), csv.as_bytes()).unwrap();
+
+        assert_eq!(result.imported, 2);
+        assert_eq!(
+            result.duplicates,
+            ["line 2: Pushups already exists on 01.10.2026"]
+        );
+        assert_eq!(result.projects[0].tasks.len(), 3);
+        assert_eq!(result.projects[2].title, "03.10.2026");
+
+        let invalid = "date,exercise,status,priority,target,sets,weight
+01.10.2026,lunges,50,7,,,
+";
+        assert!(CsvFile::import(get_projects(), invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn import_rejects_the_numbers_refused_by_the_editor() {
+        for (row, error) in [
+            ("lunges,50,0,,,NaN", "line 2: invalid weight NaN"),
+            ("lunges,50,0,inf,,", "line 2: invalid target inf"),
+            ("lunges,50,0,,,-5", "line 2: invalid weight -5"),
+        ] {
+            let csv =
+                format!("date,exercise,status,priority,target,sets,weight\n01.10.2026,{row}\n");
+
+            let result = CsvFile::import(get_projects(), csv.as_bytes());
+
+            assert_eq!(result.err().map(|e| e.to_string()), Some(error.to_string()));
+        }
+
+        let csv = "date,exercise,status,priority,target,sets,weight,kind,progress
+01.10.2026,plank,0,0,60,,,duration,-1
+";
+        assert_eq!(
+            CsvFile::import(get_projects(), csv.as_bytes())
+                .err()
+                .map(|e| e.to_string()),
+            Some("line 2: invalid progress -1".to_string())
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use std::error::Error;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+
+use crate::{
+    project::Project,
+    task::{Task, TaskKind, Track, TASK_STATUS_DONE},
+};
+
+// Mean radius of the earth, used by the haversine formula
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// Session recorded by a watch (i.e. a run or a ride)
+#[derive(Debug)]
+pub struct GpxTrack {
+    pub title: String,
+    pub start: DateTime<FixedOffset>,
+    pub track: Track,
+}
+
+struct GpxPoint {
+    lat: f64,
+    lon: f64,
+    ele: Option<f64>,
+    time: Option<DateTime<FixedOffset>>,
+}
+
+pub struct Gpx;
+
+impl Gpx {
+    // Distance between two points on the earth, cf. https://en.wikipedia.org/wiki/Haversine_formula
+    fn get_distance_km(a: &GpxPoint, b: &GpxPoint) -> f64 {
+        let d_lat = (b.lat - a.lat).to_radians();
+        let d_lon = (b.lon - a.lon).to_radians();
+
+        let h = (d_lat / 2.0).sin().powi(2)
+            + a.lat.to_radians().cos() * b.lat.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+    }
+
+    fn get_child_text<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<&'a str> {
+        node.children()
+            .find(|n| n.has_tag_name(name))
+            .and_then(|n| n.text())
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+    }
+
+    fn parse_point(node: roxmltree::Node) -> Result<GpxPoint, Box<dyn Error>> {
+        let coordinate = |name: &str| -> Result<f64, Box<dyn Error>> {
+            node.attribute(name)
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| format!("invalid {name} of a point").into())
+        };
+
+        let time = match Gpx::get_child_text(node, "time") {
+            Some(time) => Some(DateTime::parse_from_rfc3339(time)?),
+            None => None,
+        };
+
+        Ok(GpxPoint {
+            lat: coordinate("lat")?,
+            lon: coordinate("lon")?,
+            ele: Gpx::get_child_text(node, "ele").and_then(|e| e.parse().ok()),
+            time,
+        })
+    }
+
+    // Distance and elevation gain are summed in each segment, the duration goes
+    // from the first to the last point of the track (pauses included)
+    pub fn parse(text: &str) -> Result<GpxTrack, Box<dyn Error>> {
+        let document = roxmltree::Document::parse(text)?;
+
+        let Some(track) = document.descendants().find(|n| n.has_tag_name("trk")) else {
+            return Err("the file has no track".into());
+        };
+
+        let mut distance_km = 0.0;
+        let mut elevation_gain_m = 0.0;
+        let mut times = vec![];
+
+        for segment in track.children().filter(|n| n.has_tag_name("trkseg")) {
+            let points = segment
+                .children()
+                .filter(|n| n.has_tag_name("trkpt"))
+                .map(Gpx::parse_point)
+                .collect::<Result<Vec<GpxPoint>, _>>()?;
+
+            for pair in points.windows(2) {
+                distance_km += Gpx::get_distance_km(&pair[0], &pair[1]);
+
+                if let (Some(a), Some(b)) = (pair[0].ele, pair[1].ele) {
+                    elevation_gain_m += (b - a).max(0.0);
+                }
+            }
+
+            times.extend(points.iter().filter_map(|p| p.time));
+        }
+
+        let (Some(start), Some(end)) = (times.iter().min(), times.iter().max()) else {
+            return Err("the track has no time".into());
+        };
+
+        let title = Gpx::get_child_text(track, "name")
+            .or(Gpx::get_child_text(track, "type"))
+            .unwrap_or("track")
+            .to_string();
+
+        Ok(GpxTrack {
+            title,
+            start: *start,
+            track: Track {
+                start: start.to_rfc3339(),
+                distance_km,
+                duration_s: (*end - *start).num_seconds().max(0) as u64,
+                elevation_gain_m,
+            },
+        })
+    }
+
+    // Day of the track, in the local time like the days of the TUI
+    pub fn get_date(gpx_track: &GpxTrack) -> NaiveDate {
+        gpx_track.start.with_timezone(&Local).date_naive()
+    }
+
+    // Add the track as a done distance task, returns its title.
+    // The title gets a number if the day has already a task with it (i.e. "run 2")
+    pub fn add_track(project: &mut Project, gpx_track: GpxTrack) -> Result<String, String> {
+        if project.tasks.iter().any(|t| {
+            t.track
+                .as_ref()
+                .is_some_and(|t| t.start == gpx_track.track.start)
+        }) {
+            return Err(format!(
+                "the track of {} is already imported on {}",
+                gpx_track.track.start, project.title
+            ));
+        }
+
+        let mut title = gpx_track.title.clone();
+        let mut number = 1;
+
+        while project.tasks.iter().any(|t| t.title == title) {
+            number += 1;
+            title = format!("{} {number}", gpx_track.title);
+        }
+
+        project.tasks.push(Task {
+            title: title.clone(),
+            status: TASK_STATUS_DONE,
+            priority: 0,
+            target: 0.0,
+            progress: gpx_track.track.distance_km as f32,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Distance,
+            track: Some(gpx_track.track),
+        });
+
+        Ok(title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 points going north, about 1.11km apart
+    static GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="watch" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk>
+    <name>run</name>
+    <trkseg>
+      <trkpt lat="45.00" lon="7.00"><ele>200</ele><time>2026-10-12T07:00:00Z</time></trkpt>
+      <trkpt lat="45.01" lon="7.00"><ele>210</ele><time>2026-10-12T07:05:00Z</time></trkpt>
+      <trkpt lat="45.02" lon="7.00"><ele>205</ele><time>2026-10-12T07:10:30Z</time></trkpt>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    #[test]
+    fn parse_computes_distance_duration_and_elevation_gain() {
+        let gpx_track = Gpx::parse(GPX).unwrap();
+
+        assert_eq!(gpx_track.title, "run");
+        assert!((gpx_track.track.distance_km - 2.224).abs() < 0.01);
+        assert_eq!(gpx_track.track.duration_s, 630);
+        assert_eq!(gpx_track.track.elevation_gain_m, 10.0);
+    }
+
+    #[test]
+    fn add_track_refuses_the_same_track_twice() {
+        let mut project = Project {
+            title: "12.10.2026".to_string(),
+            tasks: vec![],
+            day_off: None,
+        };
+
+        assert_eq!(
+            Gpx::add_track(&mut project, Gpx::parse(GPX).unwrap()),
+            Ok("run".to_string())
+        );
+        assert!(Gpx::add_track(&mut project, Gpx::parse(GPX).unwrap()).is_err());
+
+        let mut other = Gpx::parse(GPX).unwrap();
+        other.track.start = "2026-10-12T18:00:00+00:00".to_string();
+
+        assert_eq!(Gpx::add_track(&mut project, other), Ok("run 2".to_string()));
+        assert_eq!(project.tasks[0].kind, TaskKind::Distance);
+    }
+}
@@ -0,0 +1,191 @@
+use std::io;
+
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::{
+    journal::{Journal, JournalFormat},
+    project::{Project, PROJECT_DATE_FORMAT},
+    stats::Stats,
+};
+
+// Lines longer than this are folded, cf. https://www.rfc-editor.org/rfc/rfc5545#section-3.1
+const ICS_LINE_LENGTH: usize = 75;
+
+// iCalendar file with an all-day event for each completed day and each day still to do
+pub struct Ics;
+
+impl Ics {
+    // cf. https://www.rfc-editor.org/rfc/rfc5545#section-3.3.11
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n")
+    }
+
+    // Split the line in chunks of 75 bytes at most, the next ones start with a space
+    fn fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut length = 0;
+
+        for c in line.chars() {
+            if length + c.len_utf8() > ICS_LINE_LENGTH {
+                folded.push_str("\r\n ");
+                length = 1;
+            }
+
+            folded.push(c);
+            length += c.len_utf8();
+        }
+
+        folded
+    }
+
+    fn get_event(project: &Project, date: NaiveDate, is_done: bool, stamp: &str) -> Vec<String> {
+        let count = match project.tasks.len() {
+            1 => "1 exercise".to_string(),
+            count => format!("{count} exercises"),
+        };
+
+        let summary = if is_done {
+            format!("Workout done ({count})")
+        } else {
+            format!("Workout planned ({count})")
+        };
+
+        let description: Vec<String> = project
+            .tasks
+            .iter()
+            .map(|t| Journal::get_task_line(t, JournalFormat::Markdown))
+            .collect();
+
+        vec![
+            "BEGIN:VEVENT".to_string(),
+            // The uid is bound to the day, so a new export updates the imported events
+            format!(
+                "UID:{}@{}",
+                date.format("%Y%m%d"),
+                env!("CARGO_PKG_NAME").replace('_', "-")
+            ),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (date + Duration::days(1)).format("%Y%m%d")
+            ),
+            format!("SUMMARY:{}", Ics::escape(&summary)),
+            format!("DESCRIPTION:{}", Ics::escape(&description.join("\n"))),
+            if is_done {
+                "STATUS:CONFIRMED".to_string()
+            } else {
+                "STATUS:TENTATIVE".to_string()
+            },
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]
+    }
+
+    // Only the days titled by a date and with exercises are exported: the completed ones
+    // and the ones from `today` that are not completed yet
+    pub fn export(
+        projects: &[Project],
+        today: NaiveDate,
+        mut writer: impl io::Write,
+    ) -> io::Result<()> {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!(
+                "PRODID:-//{}//{}//EN",
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION")
+            ),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+
+        for project in projects {
+            let Ok(date) = NaiveDate::parse_from_str(&project.title, PROJECT_DATE_FORMAT) else {
+                continue;
+            };
+
+            let Some(completion) = Stats::get_completion(project) else {
+                continue;
+            };
+
+            let is_done = completion >= 100.0;
+
+            if is_done || date >= today {
+                lines.extend(Ics::get_event(project, date, is_done, &stamp));
+            }
+        }
+
+        lines.push("END:VCALENDAR".to_string());
+
+        for line in lines {
+            write!(writer, "{}\r\n", Ics::fold(&line))?;
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Status, Task, TaskKind, TASK_STATUS_DONE};
+
+    fn get_project(title: &str, status: Status) -> Project {
+        Project {
+            title: title.to_string(),
+            tasks: vec![Task {
+                title: "pushups, squats".to_string(),
+                status,
+                priority: 0,
+                target: 0.0,
+                progress: 0.0,
+                sets: vec![],
+                weight: 0.0,
+                kind: TaskKind::Reps,
+                track: None,
+            }],
+            day_off: None,
+        }
+    }
+
+    #[test]
+    fn completed_and_coming_days_are_all_day_events() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut done_project = get_project("10.10.2026", TASK_STATUS_DONE);
+        let mut plank = done_project.tasks[0].clone();
+        plank.title = "plank".to_string();
+        done_project.tasks.push(plank);
+
+        let projects = vec![
+            done_project,
+            // Past and not completed
+            get_project("11.10.2026", Status::new(50)),
+            get_project("20.10.2026", Status::new(0)),
+        ];
+
+        let mut ics = vec![];
+        Ics::export(&projects, today, &mut ics).unwrap();
+        let ics = String::from_utf8(ics).unwrap();
+
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261010\r\nDTEND;VALUE=DATE:20261011\r\n"));
+        assert!(ics.contains("SUMMARY:Workout done (2 exercises)\r\n"));
+        assert!(ics.contains("SUMMARY:Workout planned (1 exercise)\r\n"));
+        assert!(ics.contains("DESCRIPTION:- [x] pushups\\, squats (100%)\\n- [x] plank (100%)\r\n"));
+        assert!(!ics.contains("DTSTART;VALUE=DATE:20261011"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let line = "x".repeat(100);
+        let folded = Ics::fold(&line);
+
+        assert_eq!(folded, format!("{}\r\n {}", "x".repeat(75), "x".repeat(25)));
+    }
+}
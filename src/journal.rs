@@ -0,0 +1,149 @@
+use std::io;
+
+use crate::{project::Project, task::Task, util::Util};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum JournalFormat {
+    Markdown,
+    Org,
+}
+
+// Written training journal, each day is a heading with its exercises as a checklist
+pub struct Journal;
+
+impl Journal {
+    fn get_checkbox(task: &Task, format: JournalFormat) -> &'static str {
+        match (format, task.status.percentage()) {
+            (JournalFormat::Markdown, 100) => "[x]",
+            (JournalFormat::Org, 100) => "[X]",
+            // Org-mode has a checkbox for the partial progress
+            (JournalFormat::Org, p) if p > 0 => "[-]",
+            _ => "[ ]",
+        }
+    }
+
+    // i.e. "- [ ] !!! pushups (75%) 10+12+8 = 30/36 @ 20kg"
+    pub fn get_task_line(task: &Task, format: JournalFormat) -> String {
+        let mut line = format!("- {} ", Journal::get_checkbox(task, format));
+
+        if task.priority != 0 {
+            line.push_str(&format!("{} ", Util::get_priority_indicator(task.priority)));
+        }
+
+        line.push_str(&format!("{} ({}%)", task.title, task.status));
+
+        if let Some(stats) = Task::get_stats_repr(task) {
+            line.push_str(&format!(" {stats}"));
+        }
+
+        line
+    }
+
+    // The days without exercises are skipped, the days off have their reason instead
+    pub fn export(
+        projects: &[Project],
+        format: JournalFormat,
+        mut writer: impl io::Write,
+    ) -> io::Result<()> {
+        let heading = match format {
+            JournalFormat::Markdown => "#",
+            JournalFormat::Org => "*",
+        };
+
+        let mut is_first = true;
+
+        for project in projects
+            .iter()
+            .filter(|p| !p.tasks.is_empty() || p.day_off.is_some())
+        {
+            if !is_first {
+                writeln!(writer)?;
+            }
+            is_first = false;
+
+            writeln!(writer, "{heading} {}", project.title)?;
+            writeln!(writer)?;
+
+            if let Some(day_off) = &project.day_off {
+                write!(writer, "Day off ({})", day_off.reason.get_name())?;
+
+                if !day_off.note.is_empty() {
+                    write!(writer, ": {}", day_off.note)?;
+                }
+
+                writeln!(writer)?;
+                continue;
+            }
+
+            for task in project.tasks.iter() {
+                writeln!(writer, "{}", Journal::get_task_line(task, format))?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{DayOff, DayOffReason};
+    use crate::task::{Status, TaskKind, TASK_STATUS_DONE};
+
+    fn get_task(title: &str, status: Status, priority: u8) -> Task {
+        Task {
+            title: title.to_string(),
+            status,
+            priority,
+            target: 0.0,
+            progress: 0.0,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Reps,
+            track: None,
+        }
+    }
+
+    #[test]
+    fn days_are_headings_with_a_checklist() {
+        let projects = vec![
+            Project {
+                title: "01.10.2026".to_string(),
+                tasks: vec![
+                    get_task("pushups", TASK_STATUS_DONE, 0),
+                    get_task("squats", Status::new(50), 1),
+                ],
+                day_off: None,
+            },
+            Project {
+                title: "02.10.2026".to_string(),
+                tasks: vec![],
+                day_off: None,
+            },
+            Project {
+                title: "03.10.2026".to_string(),
+                tasks: vec![get_task("pushups", Status::new(0), 0)],
+                day_off: Some(DayOff {
+                    reason: DayOffReason::Sick,
+                    note: "flu".to_string(),
+                }),
+            },
+        ];
+
+        let mut markdown = vec![];
+        Journal::export(&projects, JournalFormat::Markdown, &mut markdown).unwrap();
+
+        assert_eq!(
+            String::from_utf8(markdown).unwrap(),
+            "# 01.10.2026\n\n- [x] pushups (100%)\n- [ ] !!! squats (50%)\n\n# 03.10.2026\n\nDay off (sick): flu\n"
+        );
+
+        let mut org = vec![];
+        Journal::export(&projects, JournalFormat::Org, &mut org).unwrap();
+
+        assert_eq!(
+            String::from_utf8(org).unwrap(),
+            "* 01.10.2026\n\n- [X] pushups (100%)\n- [-] !!! squats (50%)\n\n* 03.10.2026\n\nDay off (sick): flu\n"
+        );
+    }
+}
@@ -1,25 +1,52 @@
-use chrono::NaiveDate;
+use chrono::{Duration, Local, NaiveDateTime};
 use serde_json::{from_str, to_string, Value};
 use std::{
+    env,
     error::Error,
     fs::{self, File},
-    io::Write,
+    io::{self, Write},
     path::{Path, PathBuf},
     sync::Mutex,
 };
 
-use crate::{
-    migration::{Migration, JSON_VERSIONS},
-    project::Project,
-};
+use crate::{migration::Migration, project::Project};
 
 pub struct Json;
 
 static DIR_CONFIG_NAME: &str = env!("CARGO_PKG_NAME");
+static DIR_BACKUPS_NAME: &str = "backups";
+static DIR_ENV_NAME: &str = "BASILK_PE_DIR";
 static VERSION: Mutex<String> = Mutex::new(String::new());
+// Directory chosen from the command line (--data-dir or --portable)
+static DIR_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
 
 impl Json {
+    pub fn set_dir_path(path: PathBuf) {
+        *DIR_PATH.lock().unwrap() = Some(path)
+    }
+
+    // Directory next to the binary used in portable mode
+    pub fn get_portable_dir_path() -> Option<PathBuf> {
+        let mut path = env::current_exe().ok()?.parent()?.to_path_buf();
+        path.push(format!("{DIR_CONFIG_NAME}_data"));
+
+        Some(path)
+    }
+
+    // Priority: command line > environment variable > portable directory (if exists) > config directory
     pub fn get_dir_path() -> PathBuf {
+        if let Some(path) = DIR_PATH.lock().unwrap().clone() {
+            return path;
+        }
+
+        if let Some(path) = env::var_os(DIR_ENV_NAME).filter(|p| !p.is_empty()) {
+            return PathBuf::from(path);
+        }
+
+        if let Some(path) = Json::get_portable_dir_path().filter(|p| p.is_dir()) {
+            return path;
+        }
+
         let mut path = dirs::config_dir().unwrap();
         path.push(DIR_CONFIG_NAME);
 
@@ -34,110 +61,360 @@ impl Json {
         path
     }
 
+    pub fn get_backups_dir_path() -> PathBuf {
+        let mut path = Json::get_dir_path();
+        path.push(DIR_BACKUPS_NAME);
+
+        path
+    }
+
+    // Versions of the json files found in the directory
+    fn get_versions_from_files() -> Vec<&'static str> {
+        Migration::get_versions()
+            .into_iter()
+            .filter(|version| Path::new(&Json::get_json_path(version.to_string())).is_file())
+            .collect()
+    }
+
+    // Write into a temporary file and then replace the original one,
+    // so a crash in the middle of the write never leaves a corrupted file
+    fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, path)?;
+
+        // Persist the rename too (not supported on every platform)
+        if let Some(dir) = path.parent() {
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+
+        Ok(())
+    }
+
+    // "<timestamp>.<name>" (i.e. the name "f2d07.json"), the milliseconds keep apart the backups
+    // of the same second and a name already taken moves to the next millisecond, so the names
+    // stay sorted by time
+    pub fn get_backup_path(dir: &Path, name: &str, mut time: NaiveDateTime) -> PathBuf {
+        loop {
+            let path = dir.join(format!("{}.{name}", time.format("%Y%m%d-%H%M%S-%3f")));
+
+            if !path.exists() {
+                return path;
+            }
+
+            time += Duration::milliseconds(1);
+        }
+    }
+
+    // Copy the json file into the backups directory
+    pub fn backup() -> io::Result<Option<PathBuf>> {
+        let Some(version) = Json::get_versions_from_files().first().copied() else {
+            return Ok(None);
+        };
+
+        fs::create_dir_all(Json::get_backups_dir_path())?;
+
+        let path = Json::get_backup_path(
+            &Json::get_backups_dir_path(),
+            &format!("{version}.json"),
+            Local::now().naive_local(),
+        );
+
+        let json = fs::read(Json::get_json_path(version.to_string()))?;
+        Json::write_atomic(&path, &json)?;
+
+        Ok(Some(path))
+    }
+
+    // Names of the backups sorted from the oldest to the newest
+    pub fn get_backups() -> Vec<String> {
+        Json::get_backups_in(&Json::get_backups_dir_path(), ".json")
+    }
+
+    // Names of the backups with the extension (the sqlite ones are next to the json ones)
+    pub fn get_backups_in(dir: &Path, extension: &str) -> Vec<String> {
+        let mut backups: Vec<String> = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.ends_with(extension))
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        backups.sort();
+        backups
+    }
+
+    // Keep only the last `keep` backups
+    pub fn prune_backups(keep: usize) -> io::Result<()> {
+        Json::prune_backups_in(&Json::get_backups_dir_path(), keep, ".json")
+    }
+
+    pub fn prune_backups_in(dir: &Path, keep: usize, extension: &str) -> io::Result<()> {
+        let backups = Json::get_backups_in(dir, extension);
+
+        for name in backups.iter().take(backups.len().saturating_sub(keep)) {
+            fs::remove_file(dir.join(name))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn restore_backup(name: &str) -> Result<(), Box<dyn Error>> {
+        // Only a name listed by `--backups`, a path could install any file as the data
+        if !Json::get_backups().iter().any(|b| b == name) {
+            return Err(format!("backup \"{name}\" not found").into());
+        }
+
+        let mut backup_path = Json::get_backups_dir_path();
+        backup_path.push(name);
+
+        if !backup_path.is_file() {
+            return Err(format!("backup \"{name}\" not found").into());
+        }
+
+        // The backup name is "<timestamp>.<version>.json"
+        let version = name
+            .split('.')
+            .nth(1)
+            .and_then(|v| {
+                Migration::get_versions()
+                    .into_iter()
+                    .find(|version| *version == v)
+            })
+            .ok_or(format!("backup \"{name}\" has an unknown version"))?;
+
+        let json_raw = fs::read_to_string(&backup_path)?;
+        from_str::<Vec<Value>>(&json_raw)?;
+
+        // Save the current data before replacing it, so the restore can be undone
+        Json::backup()?;
+
+        for current_version in Json::get_versions_from_files() {
+            fs::remove_file(Json::get_json_path(current_version.to_string()))?;
+        }
+
+        // Migrations (if needed) are applied at the next start
+        Json::write_atomic(
+            &Json::get_json_path(version.to_string()),
+            json_raw.as_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    fn read_values(version: &str) -> Result<Vec<Value>, Box<dyn Error>> {
+        let json_raw = fs::read_to_string(Json::get_json_path(version.to_string()))?;
+
+        Ok(from_str::<Vec<Value>>(&json_raw)?)
+    }
+
+    // Replace the json file with the migrated one, the original is kept in the backups
+    fn replace_file(
+        version: &str,
+        new_version: &str,
+        json: &[Value],
+    ) -> Result<(), Box<dyn Error>> {
+        Json::backup()?;
+
+        Json::write_atomic(
+            &Json::get_json_path(new_version.to_string()),
+            to_string(json)?.as_bytes(),
+        )?;
+
+        if version != new_version {
+            fs::remove_file(Json::get_json_path(version.to_string()))?;
+        }
+
+        Ok(())
+    }
+
     pub fn check() -> Result<bool, Box<dyn Error>> {
         fs::create_dir_all(Json::get_dir_path())?;
 
         // Create the state to save the json version
         let mut version_state = VERSION.lock().unwrap();
+        version_state.clear();
+
+        let last_version = Migration::get_last_version();
 
         // Pick the version from the internal file
-        let mut json_version_from_file: Vec<&str> = JSON_VERSIONS
-            .into_iter()
-            .filter(|version| Path::new(&Json::get_json_path(version.to_string())).is_file())
-            .collect();
+        let Some(version) = Json::get_versions_from_files().first().copied() else {
+            // If the file doesn't exist create a new one with the last version
+            let path = Json::get_json_path(last_version.to_string());
 
-        // If the file doesn't exist create a new one with the last version
-        if json_version_from_file.is_empty() {
-            let last_json_version = JSON_VERSIONS.last().unwrap();
-            let path = Json::get_json_path(last_json_version.to_string());
+            let mut file = File::create(path)?;
+            file.write_all(b"[]")?;
 
-            let mut file = File::create(path).unwrap();
-            let _ = file.write_all(b"[]");
+            version_state.push_str(last_version);
 
-            json_version_from_file = vec![last_json_version];
-            version_state.push_str(json_version_from_file[0]);
+            return Ok(false);
+        };
+
+        let steps = Migration::get_pending(version);
+
+        if steps.is_empty() {
+            version_state.push_str(version);
 
             return Ok(false);
         }
 
-        // Save into the internal state the last json version
-        version_state.push_str(json_version_from_file[0]);
-
-        // Read the internal file
-        let path = Json::get_json_path(json_version_from_file[0].to_string());
-        let json_raw = fs::read_to_string(&path).unwrap();
-        let json = from_str::<Vec<Value>>(&json_raw).unwrap();
+        let json = Json::read_values(version)?;
 
+        // An empty file only needs the name of the last version
         if json.is_empty() {
+            fs::rename(
+                Json::get_json_path(version.to_string()),
+                Json::get_json_path(last_version.to_string()),
+            )?;
+            version_state.push_str(last_version);
+
             return Ok(false);
         }
 
-        // Load all migrations
-        let migrations = Migration::get_migrations(json_version_from_file[0], json);
+        // Apply the migrations one after the other
+        let new_json = steps.iter().fold(json, |json, step| (step.up)(json));
 
-        if migrations.is_empty() {
-            return Ok(false);
+        Json::replace_file(version, last_version, &new_json)?;
+        version_state.push_str(last_version);
+
+        Ok(true)
+    }
+
+    // Like `check` without writing anything, for a session without the lock: the json file
+    // has to be at the last version already (the session holding the lock migrates it)
+    pub fn check_read_only() -> Result<(), Box<dyn Error>> {
+        let mut version_state = VERSION.lock().unwrap();
+        version_state.clear();
+
+        let version = Json::get_versions_from_files()
+            .first()
+            .copied()
+            .ok_or("no json file found")?;
+
+        if !Migration::get_pending(version).is_empty() {
+            return Err(format!(
+                "the json file (version {version}) is used by an older session, close it and retry"
+            )
+            .into());
         }
 
-        // Loop thru all migrations and apply them!
-        for (version, migration) in migrations.iter() {
-            let path = Json::get_json_path(version_state.to_string());
-            let new_path = Json::get_json_path(version.to_string());
+        version_state.push_str(version);
+
+        Ok(())
+    }
+
+    // Migrate the json file to the last version (or revert the last migration with `rollback`).
+    // Returns the versions and the changes, empty if there is nothing to do
+    pub fn migrate(rollback: bool, dry_run: bool) -> Result<Vec<String>, Box<dyn Error>> {
+        let version = Json::get_versions_from_files()
+            .first()
+            .copied()
+            .ok_or("no json file found")?;
+
+        let json = Json::read_values(version)?;
 
-            let new_json = migration;
+        let (new_version, new_json) = if rollback {
+            let (step, previous_version) = Migration::get_rollback(version).ok_or(format!(
+                "the version {version} is the first one, nothing to roll back"
+            ))?;
 
-            fs::write(&path, new_json).unwrap();
-            fs::rename(&path, new_path)?;
+            (previous_version, (step.down)(json.clone()))
+        } else {
+            let new_json = Migration::get_pending(version)
+                .iter()
+                .fold(json.clone(), |json, step| (step.up)(json));
 
-            // Save into the internal state the json version of the last migration applied
-            version_state.clear();
-            version_state.push_str(version)
+            (Migration::get_last_version(), new_json)
+        };
+
+        if new_version == version {
+            return Ok(vec![]);
         }
 
-        Ok(true)
+        let mut lines = vec![format!("{version} -> {new_version}")];
+        lines.extend(Migration::diff(&json, &new_json));
+
+        if !dry_run {
+            Json::replace_file(version, new_version, &new_json)?;
+        }
+
+        Ok(lines)
     }
 
-    pub fn read() -> Vec<Project> {
+    // Path of the json file with the version found by `check`
+    pub fn get_current_path() -> PathBuf {
         let version = VERSION.lock().unwrap().to_string();
-        let path = Json::get_json_path(version);
 
+        Json::get_json_path(version)
+    }
+
+    pub fn try_read(path: &Path) -> Result<Vec<Project>, Box<dyn Error>> {
         // Read the JSON file
-        let json = fs::read_to_string(path).unwrap();
+        let json = fs::read_to_string(path)?;
 
         // Parse the JSON into a vector of projects
-        let projects = from_str::<Vec<Project>>(&json).unwrap();
+        let projects = from_str::<Vec<Project>>(&json)?;
 
-        // Parse dates and handle invalid dates gracefully
-        let mut projects_with_dates: Vec<_> = projects
-            .into_iter()
-            .map(|p| {
-                // Attempt to parse the title as a date
-                let date = NaiveDate::parse_from_str(&p.title, "%d.%m.%Y").ok();
-                (p, date)
-            })
-            .collect();
-
-        // Sort projects by date (projects with invalid dates will be placed first)
-        projects_with_dates.sort_by(|(_, date1), (_, date2)| {
-            match (date1, date2) {
-                (Some(d1), Some(d2)) => d1.cmp(d2), // Both dates are valid, compare them
-                (Some(_), None) => std::cmp::Ordering::Greater, // Valid dates come after invalid dates
-                (None, Some(_)) => std::cmp::Ordering::Less, // Invalid dates come before valid dates
-                (None, None) => std::cmp::Ordering::Equal, // Both dates are invalid, keep their order
-            }
-        });
+        Ok(Project::sort_by_date(projects))
+    }
 
-        // Extract the sorted projects (discard the dates)
-        let sorted_projects: Vec<Project> =
-            projects_with_dates.into_iter().map(|(p, _)| p).collect();
+    pub fn read(path: &Path) -> Vec<Project> {
+        Json::try_read(path).unwrap()
+    }
 
-        sorted_projects
+    pub fn write(path: &Path, projects: Vec<Project>) {
+        Json::write_atomic(path, to_string(&projects).unwrap().as_bytes())
+            .expect("Unable to save the json file");
     }
+}
 
-    pub fn write(projects: Vec<Project>) {
-        let version = VERSION.lock().unwrap().to_string();
-        let path = Json::get_json_path(version);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn backups_of_the_same_second_are_kept_and_pruned_by_age() {
+        let dir = env::temp_dir().join(format!("{DIR_CONFIG_NAME}_backups_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let time = NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_milli_opt(8, 15, 0, 999)
+            .unwrap();
+
+        let mut paths = vec![];
+        for _ in 0..4 {
+            let path = Json::get_backup_path(&dir, "f2d07.json", time);
+            fs::write(&path, "[]").unwrap();
+            paths.push(path.file_name().unwrap().to_string_lossy().to_string());
+        }
+
+        assert_eq!(paths[0], "20261018-081500-999.f2d07.json");
+        // The next free millisecond, even after the end of the second
+        assert_eq!(paths[1], "20261018-081501-000.f2d07.json");
+        assert_eq!(Json::get_backups_in(&dir, ".json"), paths);
+
+        // Only the newest ones are kept, the other files are ignored
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        fs::write(dir.join("20261018-081459-000.basilk_pe.sqlite"), "").unwrap();
+        Json::prune_backups_in(&dir, 3, ".json").unwrap();
+        assert_eq!(Json::get_backups_in(&dir, ".json"), paths[1..]);
+
+        Json::prune_backups_in(&dir, 0, ".json").unwrap();
+        assert!(Json::get_backups_in(&dir, ".json").is_empty());
+        assert!(dir.join("notes.txt").is_file());
+        assert_eq!(Json::get_backups_in(&dir, ".sqlite").len(), 1);
 
-        fs::write(path, to_string(&projects).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
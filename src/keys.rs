@@ -0,0 +1,420 @@
+use std::{collections::BTreeMap, fmt};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::KeysConfig, ViewMode};
+
+// What a key does in a view, cf. the [keys] section of config.toml
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Next,
+    Previous,
+    Open,
+    Back,
+    ChangeStatus,
+    ChangePriority,
+    EditSets,
+    ChangeKind,
+    New,
+    Rename,
+    DayOff,
+    BrowseGrid,
+    GridFilter,
+    Delete,
+    PreviousWeek,
+    NextWeek,
+    PreviousDay,
+    NextDay,
+    Help,
+    Quit,
+    Confirm,
+    Cancel,
+    Overwrite,
+    Reload,
+}
+
+// A key with its modifiers, written like "j", "G", "Enter", "PageDown", "F1" or "Ctrl+d"
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn parse(value: &str) -> Result<Key, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut name = value;
+
+        // A lone "+" is the key itself
+        loop {
+            let has_prefix = |prefix: &str| {
+                name.len() > prefix.len()
+                    && name
+                        .get(..prefix.len())
+                        .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+            };
+
+            if has_prefix("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+                name = &name[5..];
+            } else if has_prefix("alt+") {
+                modifiers |= KeyModifiers::ALT;
+                name = &name[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = name.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            // The characters are case sensitive (i.e. "g" and "G")
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key \"{value}\"")),
+                },
+            },
+        };
+
+        Ok(Key { code, modifiers })
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // Shift is already part of the character (i.e. "G")
+        self.code == key.code
+            && self.modifiers == key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Key::parse(&value)
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.to_string()
+    }
+}
+
+// Name of the action in the config, i.e. "change_status"
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match toml::Value::try_from(self) {
+            Ok(toml::Value::String(name)) => write!(f, "{name}"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+// Keys of an action and its label in the footer helper
+#[derive(Clone, PartialEq, Debug)]
+pub struct Binding {
+    pub action: Action,
+    pub keys: Vec<Key>,
+    pub description: &'static str,
+}
+
+impl Binding {
+    /// Constructor for Binding
+    fn new(action: Action, keys: &[&str], description: &'static str) -> Self {
+        Binding {
+            action,
+            keys: keys.iter().map(|k| Key::parse(k).unwrap()).collect(),
+            description,
+        }
+    }
+}
+
+// Keys of the views, in the order of the footer helper
+pub struct Keys {
+    projects: Vec<Binding>,
+    tasks: Vec<Binding>,
+    grid: Vec<Binding>,
+    // Confirmation of the deletion of a day or an exercise
+    delete: Vec<Binding>,
+    // Edit made while the data was changed outside of this session
+    conflict: Vec<Binding>,
+}
+
+impl Keys {
+    /// Constructor for Keys, the keys set in the config replace the default ones of their action
+    pub fn new(config: &KeysConfig) -> Self {
+        Keys {
+            projects: Keys::apply(Keys::get_default_projects(), &config.projects),
+            tasks: Keys::apply(Keys::get_default_tasks(), &config.tasks),
+            grid: Keys::apply(Keys::get_default_grid(), &config.grid),
+            delete: Keys::apply(Keys::get_default_delete(), &config.delete),
+            conflict: Keys::apply(Keys::get_default_conflict(), &config.conflict),
+        }
+    }
+
+    // A key can't do two actions of the same view, i.e. delete = ["r"] with the rename one
+    pub fn validate(&self) -> Result<(), String> {
+        let views = [
+            ("projects", &self.projects),
+            ("tasks", &self.tasks),
+            ("grid", &self.grid),
+            ("delete", &self.delete),
+            ("conflict", &self.conflict),
+        ];
+
+        for (view, bindings) in views {
+            for (index, binding) in bindings.iter().enumerate() {
+                for other in bindings.iter().skip(index + 1) {
+                    if let Some(key) = binding.keys.iter().find(|k| other.keys.contains(k)) {
+                        return Err(format!(
+                            "the key \"{key}\" of the {view} view is bound to {} and {}",
+                            binding.action, other.action
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // The actions that don't belong to the view are ignored
+    fn apply(mut bindings: Vec<Binding>, keys: &BTreeMap<Action, Vec<Key>>) -> Vec<Binding> {
+        for binding in bindings.iter_mut() {
+            if let Some(keys) = keys.get(&binding.action) {
+                binding.keys = keys.clone();
+            }
+        }
+
+        bindings
+    }
+
+    fn get_default_projects() -> Vec<Binding> {
+        vec![
+            Binding::new(Action::Next, &["j", "Down"], "next"),
+            Binding::new(Action::Previous, &["k", "Up"], "prev"),
+            Binding::new(Action::Open, &["l", "Right", "Enter"], "go to tasks"),
+            Binding::new(Action::New, &["a", "n"], "new"),
+            Binding::new(Action::Rename, &["r"], "rename"),
+            Binding::new(Action::DayOff, &["o"], "day off"),
+            Binding::new(Action::BrowseGrid, &["g"], "browse the grid"),
+            Binding::new(Action::GridFilter, &["f"], "grid filter"),
+            Binding::new(Action::Delete, &["d"], "delete"),
+            Binding::new(Action::Help, &["?"], "help"),
+            Binding::new(Action::Quit, &["q"], "quit"),
+        ]
+    }
+
+    fn get_default_tasks() -> Vec<Binding> {
+        vec![
+            Binding::new(Action::Next, &["j", "Down"], "next"),
+            Binding::new(Action::Previous, &["k", "Up"], "prev"),
+            Binding::new(Action::Back, &["h", "Left", "Esc"], "go to projects"),
+            Binding::new(Action::ChangeStatus, &["Enter"], "change status"),
+            Binding::new(Action::ChangePriority, &["p"], "change priority"),
+            Binding::new(Action::EditSets, &["s"], "sets"),
+            Binding::new(Action::ChangeKind, &["t"], "kind"),
+            Binding::new(Action::New, &["a", "n"], "new"),
+            Binding::new(Action::Rename, &["r"], "rename"),
+            Binding::new(Action::Delete, &["d"], "delete"),
+            Binding::new(Action::Help, &["?"], "help"),
+            Binding::new(Action::Quit, &["q"], "quit"),
+        ]
+    }
+
+    fn get_default_grid() -> Vec<Binding> {
+        vec![
+            Binding::new(Action::PreviousWeek, &["h", "Left"], "prev week"),
+            Binding::new(Action::NextWeek, &["l", "Right"], "next week"),
+            Binding::new(Action::PreviousDay, &["k", "Up"], "prev day"),
+            Binding::new(Action::NextDay, &["j", "Down"], "next day"),
+            Binding::new(Action::Open, &["Enter"], "go to the day"),
+            Binding::new(Action::GridFilter, &["f"], "filter"),
+            Binding::new(Action::Back, &["Esc"], "back"),
+            Binding::new(Action::Help, &["?"], "help"),
+            Binding::new(Action::Quit, &["q"], "quit"),
+        ]
+    }
+
+    fn get_default_delete() -> Vec<Binding> {
+        vec![
+            Binding::new(Action::Confirm, &["y"], "confirm"),
+            Binding::new(Action::Cancel, &["n"], "cancel"),
+        ]
+    }
+
+    fn get_default_conflict() -> Vec<Binding> {
+        vec![
+            Binding::new(Action::Overwrite, &["o"], "overwrite with your change"),
+            Binding::new(Action::Reload, &["r"], "reload and discard it"),
+        ]
+    }
+
+    // The text editors keep <Enter> and <Esc>, the other views have no keys
+    pub fn get_bindings(&self, mode: ViewMode) -> &[Binding] {
+        match mode {
+            ViewMode::ViewProjects => &self.projects,
+            ViewMode::ViewTasks => &self.tasks,
+            ViewMode::ViewGrid => &self.grid,
+            ViewMode::DeleteProject | ViewMode::DeleteTask => &self.delete,
+            ViewMode::ConflictExternalChange => &self.conflict,
+            _ => &[],
+        }
+    }
+
+    // The first action of the view bound to the key
+    pub fn get_action(&self, mode: ViewMode, key: &KeyEvent) -> Option<Action> {
+        self.get_bindings(mode)
+            .iter()
+            .find(|b| b.keys.iter().any(|k| k.matches(key)))
+            .map(|b| b.action)
+    }
+
+    // i.e. "<j> next :: <k> prev", with the first key of each action (the help lists all of them)
+    pub fn get_footer(&self, mode: ViewMode, filter: impl Fn(Action) -> bool) -> String {
+        self.get_bindings(mode)
+            .iter()
+            .filter(|b| filter(b.action))
+            .filter_map(|b| Some(format!("<{}> {}", b.keys.first()?, b.description)))
+            .collect::<Vec<String>>()
+            .join(" :: ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_keys_replace_the_default_ones() {
+        let config: KeysConfig = toml::from_str(
+            r#"
+            [projects]
+            delete = ["x", "Ctrl+d"]
+            help = []
+
+            [grid]
+            next_week = ["PageDown"]
+            "#,
+        )
+        .unwrap();
+
+        let keys = Keys::new(&config);
+        let get_key = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        assert_eq!(
+            keys.get_action(
+                ViewMode::ViewProjects,
+                &get_key(KeyCode::Char('d'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::Delete)
+        );
+        // Without its modifier the key is another one
+        assert_eq!(
+            keys.get_action(
+                ViewMode::ViewProjects,
+                &get_key(KeyCode::Char('d'), KeyModifiers::NONE)
+            ),
+            None
+        );
+        assert_eq!(
+            keys.get_action(
+                ViewMode::ViewGrid,
+                &get_key(KeyCode::PageDown, KeyModifiers::NONE)
+            ),
+            Some(Action::NextWeek)
+        );
+        assert_eq!(
+            keys.get_action(
+                ViewMode::ViewTasks,
+                &get_key(KeyCode::Char('d'), KeyModifiers::NONE)
+            ),
+            Some(Action::Delete)
+        );
+
+        assert_eq!(
+            keys.get_footer(ViewMode::ViewProjects, |a| {
+                matches!(a, Action::Delete | Action::Help | Action::Quit)
+            }),
+            "<x> delete :: <q> quit"
+        );
+
+        assert_eq!(
+            Key::parse("ctrl+alt+F5").unwrap().to_string(),
+            "Ctrl+Alt+F5"
+        );
+        assert_eq!(Key::parse("+").unwrap().to_string(), "+");
+        assert!(Key::parse("hyper").is_err());
+    }
+
+    #[test]
+    fn a_key_bound_to_two_actions_of_a_view_is_refused() {
+        assert_eq!(Keys::new(&KeysConfig::default()).validate(), Ok(()));
+
+        let get_keys = |toml: &str| Keys::new(&toml::from_str::<KeysConfig>(toml).unwrap());
+
+        assert_eq!(
+            get_keys("[tasks]\ndelete = [\"x\", \"r\"]").validate(),
+            Err("the key \"r\" of the tasks view is bound to rename and delete".to_string())
+        );
+        assert_eq!(
+            get_keys("[conflict]\noverwrite = [\"r\"]").validate(),
+            Err("the key \"r\" of the conflict view is bound to overwrite and reload".to_string())
+        );
+        // The same key in two views is fine
+        assert_eq!(get_keys("[grid]\nnext_week = [\"d\"]").validate(), Ok(()));
+
+        let keys = get_keys("[delete]\nconfirm = [\"Enter\"]");
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        assert_eq!(
+            keys.get_action(ViewMode::DeleteTask, &enter),
+            Some(Action::Confirm)
+        );
+        assert_eq!(
+            keys.get_footer(ViewMode::DeleteProject, |_| true),
+            "<Enter> confirm :: <n> cancel"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    fs::{self, File, TryLockError},
+    io,
+    path::PathBuf,
+};
+
+use crate::json::Json;
+
+pub struct Lock;
+
+static LOCK_FILE_NAME: &str = env!("CARGO_PKG_NAME");
+
+impl Lock {
+    // The lock is taken on a dedicated file because the data file
+    // is replaced (and so unlocked) at every write
+    fn get_path() -> PathBuf {
+        let mut path = Json::get_dir_path();
+        path.push(format!("{LOCK_FILE_NAME}.lock"));
+
+        path
+    }
+
+    // None if another session is holding the lock.
+    // The lock is released when the returned file is dropped (i.e. at the exit)
+    pub fn acquire() -> io::Result<Option<File>> {
+        fs::create_dir_all(Json::get_dir_path())?;
+
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(Lock::get_path())?;
+
+        match file.try_lock() {
+            Ok(_) => Ok(Some(file)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+}
@@ -2,12 +2,18 @@ use std::{
     error::Error,
     fmt::Debug,
     io::{self, stdout},
+    process::exit,
+    time::Duration,
 };
 
+use chrono::{Local, NaiveDate};
 use cli::Cli;
 use ratatui::{
     crossterm::{
-        event::{self, Event, KeyCode, KeyEventKind},
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+            MouseButton, MouseEvent, MouseEventKind,
+        },
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
         ExecutableCommand,
     },
@@ -18,25 +24,44 @@ use tui_input::{backend::crossterm::EventHandler, Input};
 
 mod cli;
 mod config;
+mod csv_file;
+mod gpx;
+mod ics;
+mod journal;
 mod json;
+mod keys;
+mod lock;
 mod migration;
 mod project;
+mod report;
+mod stats;
+mod storage;
 mod task;
+mod theme;
 mod ui;
 mod util;
 mod view;
 
 use config::{Config, ConfigToml};
 use json::Json;
-use project::Project;
-use task::{Task, TASK_PRIORITIES, TASK_STATUSES};
-use view::View;
+use keys::{Action, Keys};
+use lock::Lock;
+use project::{Project, PROJECT_DATE_FORMAT};
+use stats::Stats;
+use storage::{read_only::ReadOnlyStorage, Storage};
+use task::{Task, TASK_PRIORITIES};
+use theme::Theme;
+use view::{
+    grid_activity::{GridActivity, GridFilter, GRID_HEIGHT},
+    View,
+};
 
-#[derive(Default, PartialEq, Debug)]
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
 pub enum ViewMode {
     #[default]
     ViewProjects,
     RenameProject,
+    ChangeDayOffProject,
     AddProject,
     DeleteProject,
 
@@ -44,12 +69,20 @@ pub enum ViewMode {
     RenameTask,
     ChangeStatusTask,
     ChangePriorityTask,
+    ChangeSetsTask,
     AddTask,
     DeleteTask,
 
+    ViewGrid,
+
     InfoMigration,
+    InfoReadOnly,
+    ConflictExternalChange,
 }
 
+// How often the data is checked for changes made outside of this session
+const EXTERNAL_CHANGE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 pub struct App {
     // TODO: Better list state mgmt
     selected_project_index: ListState,
@@ -59,11 +92,29 @@ pub struct App {
     view_mode: ViewMode,
     projects: Vec<Project>,
     config: ConfigToml,
+    storage: Box<dyn Storage>,
+    // Another session is running, the changes are discarded (and the missing days aren't created)
+    read_only: bool,
+    // The data was changed outside of this session and not reloaded yet
+    external_change: bool,
+    // Edit (view and confirm key) waiting for the choice between overwrite and reload
+    conflict: Option<(ViewMode, KeyEvent)>,
+    // Day under the cursor of the grid activity
+    selected_grid_date: Option<NaiveDate>,
+    // Last area of the grid activity, used by the mouse
+    grid_activity_area: Rect,
+    // Exercises shown by the grid activity
+    grid_filter: GridFilter,
+    theme: Theme,
+    keys: Keys,
+    // The keys of the view are listed over it
+    show_keys: bool,
 }
 
 fn init_terminal() -> Result<Terminal<impl Backend>, Box<dyn Error>> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -71,22 +122,57 @@ fn init_terminal() -> Result<Terminal<impl Backend>, Box<dyn Error>> {
 
 fn restore_terminal() -> Result<(), Box<dyn Error>> {
     disable_raw_mode()?;
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    Cli::read();
+    let command = Cli::read();
 
     color_backtrace::install();
+
+    // Only one session at a time can save the data, it is kept until the exit
+    let lock = Lock::acquire()?;
+    let read_only = lock.is_none();
+
+    let config = Config::read();
+
+    // Check the version of the json file (unused by the sqlite backend), only the session
+    // holding the lock migrates it
+    let were_applied_migrations = match (&config.storage.backend, read_only) {
+        (config::Backend::Json, false) => Json::check()?,
+        (config::Backend::Json, true) => {
+            Json::check_read_only()?;
+            false
+        }
+        (config::Backend::Sqlite, _) => false,
+    };
+
+    // Keep a copy of the data as it was at the start of the session
+    if !read_only && command.is_none() {
+        storage::backup(&config.storage.backend, config.backup.keep)?;
+    }
+
+    let mut storage = storage::open(&config.storage.backend)?;
+
+    if read_only {
+        storage = Box::new(ReadOnlyStorage::new(storage));
+    }
+
+    let mut app = App::setup(config, storage);
+    app.read_only = read_only;
+
+    // Headless commands don't start the TUI
+    if let Some(command) = command {
+        exit(Cli::run(&mut app, command));
+    }
+
     // setup terminal
     let terminal = init_terminal()?;
 
-    // Check the version of the json file
-    let were_applied_migrations = Json::check()?;
-
     // create app and run it
-    App::setup().run(terminal, were_applied_migrations)?;
+    app.run(terminal, were_applied_migrations)?;
 
     restore_terminal()?;
 
@@ -94,15 +180,25 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 impl App {
-    fn setup() -> Self {
+    fn setup(config: ConfigToml, storage: Box<dyn Storage>) -> Self {
         Self {
             selected_project_index: ListState::default().with_selected(Some(0)),
             selected_task_index: ListState::default().with_selected(Some(0)),
             selected_status_task_index: ListState::default().with_selected(Some(0)),
             selected_priority_task_index: ListState::default().with_selected(Some(0)),
             view_mode: ViewMode::default(),
-            projects: Json::read(),
-            config: Config::read(),
+            projects: storage.read(),
+            theme: Theme::new(&config.theme),
+            keys: Keys::new(&config.keys),
+            config,
+            storage,
+            read_only: false,
+            external_change: false,
+            conflict: None,
+            selected_grid_date: None,
+            grid_activity_area: Rect::default(),
+            grid_filter: GridFilter::default(),
+            show_keys: false,
         }
     }
 
@@ -121,74 +217,178 @@ impl App {
         Project::load_items(self, &mut items);
 
         let mut status_items: Vec<ListItem> = vec![];
-        Task::load_statuses_items(&mut status_items);
+        Task::load_statuses_items(self, &mut status_items);
 
         let mut priority_items: Vec<ListItem> = vec![];
-        Task::load_priority_items(&mut priority_items);
+        Task::load_priority_items(self, &mut priority_items);
 
-        Project::create(self, &mut items, "".to_string());
+        if !self.read_only {
+            Project::create(self, &mut items, "".to_string());
+        }
 
         if were_applied_migrations {
             self.view_mode = ViewMode::InfoMigration
         }
 
+        if self.read_only {
+            self.view_mode = ViewMode::InfoReadOnly
+        }
+
+        // Confirm key of an edit to apply again after a conflict
+        let mut replay: Option<KeyEvent> = None;
+
         loop {
+            if self.storage.has_changed() {
+                self.external_change = true
+            }
+
+            // Reload only when nothing is being edited, otherwise wait the edit confirm
+            if self.external_change && App::is_idle_view(self.view_mode) {
+                self.reload_external_change(&mut items);
+            }
+
             terminal.draw(|f| {
                 self.render(f, f.size(), &input, &items, &status_items, &priority_items)
             })?;
 
-            if let Event::Key(key) = event::read()? {
+            let event = match replay.take() {
+                Some(key) => Event::Key(key),
+                None => {
+                    if !event::poll(EXTERNAL_CHANGE_POLL_INTERVAL)? {
+                        continue;
+                    }
+
+                    event::read()?
+                }
+            };
+
+            if let Event::Mouse(mouse) = event {
+                self.handle_grid_mouse(mouse, &mut items);
+            }
+
+            if let Event::Key(key) = event {
                 // Capture only the "Press" event to prevent double input on Windows
                 if key.kind == KeyEventKind::Press {
                     use KeyCode::*;
+
+                    // Any key closes the list of the keys
+                    if self.show_keys {
+                        self.show_keys = false;
+                        continue;
+                    }
+
+                    // The edit would overwrite the data changed outside of this session
+                    if self.external_change && self.is_confirm_key(&key) {
+                        self.conflict = Some((self.view_mode, key));
+
+                        App::change_view(self, ViewMode::ConflictExternalChange);
+                        continue;
+                    }
+
                     match self.view_mode {
-                        ViewMode::ViewProjects => match key.code {
-                            Enter | Right | Char('l') => {
-                                if items.is_empty() {
-                                    continue;
-                                }
+                        ViewMode::ViewProjects => {
+                            match self.keys.get_action(self.view_mode, &key) {
+                                Some(Action::Open) => {
+                                    if items.is_empty() {
+                                        continue;
+                                    }
 
-                                Task::load_items(self, &mut items);
-                                self.selected_task_index.select(Some(0));
+                                    Task::load_items(self, &mut items);
+                                    self.selected_task_index.select(Some(0));
 
-                                App::change_view(self, ViewMode::ViewTasks);
-                            }
-                            Char('r') => {
-                                if items.is_empty() {
-                                    continue;
+                                    App::change_view(self, ViewMode::ViewTasks);
                                 }
+                                Some(Action::Rename) => {
+                                    if items.is_empty() {
+                                        continue;
+                                    }
 
-                                input = input
-                                    .clone()
-                                    .with_value(Project::get_current(self).title.clone());
+                                    input = input
+                                        .clone()
+                                        .with_value(Project::get_current(self).title.clone());
 
-                                App::change_view(self, ViewMode::RenameProject);
-                            }
-                            Char('a') | Char('n') => {
-                                input.reset();
-                                App::change_view(self, ViewMode::AddProject);
-                            }
-                            Char('d') => {
-                                if items.is_empty() {
-                                    continue;
+                                    App::change_view(self, ViewMode::RenameProject);
+                                }
+                                Some(Action::DayOff) => {
+                                    if items.is_empty() {
+                                        continue;
+                                    }
+
+                                    input = input.clone().with_value(
+                                        Project::get_current(self)
+                                            .day_off
+                                            .as_ref()
+                                            .map(|d| d.get_repr())
+                                            .unwrap_or_default(),
+                                    );
+
+                                    App::change_view(self, ViewMode::ChangeDayOffProject);
+                                }
+                                Some(Action::New) => {
+                                    input.reset();
+                                    App::change_view(self, ViewMode::AddProject);
                                 }
+                                Some(Action::BrowseGrid) => {
+                                    if !self.config.ui.show_grid_activity {
+                                        continue;
+                                    }
+
+                                    // The cursor starts on the selected day, if it is in the grid
+                                    let grid = self.get_grid_activity();
+                                    let date = self
+                                        .selected_project_index
+                                        .selected()
+                                        .and_then(|i| self.projects.get(i))
+                                        .and_then(|p| {
+                                            NaiveDate::parse_from_str(&p.title, PROJECT_DATE_FORMAT)
+                                                .ok()
+                                        })
+                                        .unwrap_or(grid.today);
+
+                                    self.selected_grid_date = Some(grid.move_date(date, 0));
+
+                                    App::change_view(self, ViewMode::ViewGrid);
+                                }
+                                Some(Action::GridFilter) => self.next_grid_filter(),
+                                Some(Action::Delete) => {
+                                    if items.is_empty() {
+                                        continue;
+                                    }
 
-                                App::change_view(self, ViewMode::DeleteProject);
+                                    App::change_view(self, ViewMode::DeleteProject);
+                                }
+                                Some(Action::Next) => {
+                                    self.next(&items);
+                                }
+                                Some(Action::Previous) => {
+                                    self.previous(&items);
+                                }
+                                Some(Action::Help) => self.show_keys = true,
+                                Some(Action::Quit) => {
+                                    return Ok(());
+                                }
+                                _ => {}
                             }
-                            Down | Char('j') => {
-                                self.next(&items);
+                        }
+                        ViewMode::RenameProject => match key.code {
+                            Enter => {
+                                Project::rename(self, &mut items, input.value());
+                                input.reset();
+
+                                App::change_view(self, ViewMode::ViewProjects);
                             }
-                            Up | Char('k') => {
-                                self.previous(&items);
+                            Esc => {
+                                input.reset();
+
+                                App::change_view(self, ViewMode::ViewProjects);
                             }
-                            Char('q') => {
-                                return Ok(());
+                            _ => {
+                                input.handle_event(&Event::Key(key));
                             }
-                            _ => {}
                         },
-                        ViewMode::RenameProject => match key.code {
+                        ViewMode::ChangeDayOffProject => match key.code {
                             Enter => {
-                                Project::rename(self, &mut items, input.value());
+                                Project::change_day_off(self, &mut items, input.value());
                                 input.reset();
 
                                 App::change_view(self, ViewMode::ViewProjects);
@@ -214,40 +414,48 @@ impl App {
                                 input.handle_event(&Event::Key(key));
                             }
                         },
-                        ViewMode::DeleteProject => match key.code {
-                            Char('y') => {
+                        ViewMode::DeleteProject => match self.keys.get_action(self.view_mode, &key)
+                        {
+                            Some(Action::Confirm) => {
                                 Project::delete(self, &mut items);
                                 self.selected_project_index.select_previous();
 
                                 App::change_view(self, ViewMode::ViewProjects);
                             }
-                            Char('n') => {
+                            Some(Action::Cancel) => {
                                 App::change_view(self, ViewMode::ViewProjects);
                             }
                             _ => {}
                         },
 
-                        ViewMode::ViewTasks => match key.code {
-                            Esc | Left | Char('h') => {
+                        ViewMode::ViewTasks => match self.keys.get_action(self.view_mode, &key) {
+                            Some(Action::Back) => {
                                 Project::load_items(self, &mut items);
 
                                 App::change_view(self, ViewMode::ViewProjects);
                             }
-                            Enter => {
+                            Some(Action::ChangeStatus) => {
                                 if items.is_empty() {
                                     continue;
                                 }
 
-                                let index = TASK_STATUSES
-                                    .into_iter()
-                                    .position(|t| t == Task::get_current(self).status)
-                                    .unwrap();
+                                // The status may be missing from the scale (i.e. the scale was
+                                // changed), the one below it is selected
+                                let status = Task::get_current(self).status;
+                                let index = self
+                                    .config
+                                    .status
+                                    .scale
+                                    .get_statuses()
+                                    .iter()
+                                    .rposition(|s| *s <= status)
+                                    .unwrap_or(0);
 
                                 self.selected_status_task_index.select(Some(index));
 
                                 App::change_view(self, ViewMode::ChangeStatusTask);
                             }
-                            Char('p') => {
+                            Some(Action::ChangePriority) => {
                                 if items.is_empty() {
                                     continue;
                                 }
@@ -261,7 +469,25 @@ impl App {
 
                                 App::change_view(self, ViewMode::ChangePriorityTask);
                             }
-                            Char('r') => {
+                            Some(Action::EditSets) => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                input = input.clone().with_value(Task::get_sets_input_value(
+                                    Task::get_current(self),
+                                ));
+
+                                App::change_view(self, ViewMode::ChangeSetsTask);
+                            }
+                            Some(Action::ChangeKind) => {
+                                if items.is_empty() {
+                                    continue;
+                                }
+
+                                Task::change_kind(self, &mut items);
+                            }
+                            Some(Action::Rename) => {
                                 if items.is_empty() {
                                     continue;
                                 }
@@ -272,25 +498,26 @@ impl App {
 
                                 App::change_view(self, ViewMode::RenameTask);
                             }
-                            Char('a') | Char('n') => {
+                            Some(Action::New) => {
                                 input.reset();
 
                                 App::change_view(self, ViewMode::AddTask);
                             }
-                            Char('d') => {
+                            Some(Action::Delete) => {
                                 if items.is_empty() {
                                     continue;
                                 }
 
                                 App::change_view(self, ViewMode::DeleteTask);
                             }
-                            Down | Char('j') => {
+                            Some(Action::Next) => {
                                 self.next(&items);
                             }
-                            Up | Char('k') => {
+                            Some(Action::Previous) => {
                                 self.previous(&items);
                             }
-                            Char('q') => {
+                            Some(Action::Help) => self.show_keys = true,
+                            Some(Action::Quit) => {
                                 return Ok(());
                             }
                             _ => {}
@@ -316,7 +543,7 @@ impl App {
                                 Task::change_status(
                                     self,
                                     &mut items,
-                                    TASK_STATUSES
+                                    self.config.status.scale.get_statuses()
                                         [self.selected_status_task_index.selected().unwrap()],
                                 );
 
@@ -324,16 +551,15 @@ impl App {
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
 
-                            Down | Char('j') => {
-                                self.next(&status_items);
-                            }
-                            Up | Char('k') => {
-                                self.previous(&status_items);
-                            }
                             Esc => {
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
-                            _ => {}
+                            // The keys moving in the list of the exercises
+                            _ => match self.keys.get_action(ViewMode::ViewTasks, &key) {
+                                Some(Action::Next) => self.next(&status_items),
+                                Some(Action::Previous) => self.previous(&status_items),
+                                _ => {}
+                            },
                         },
                         ViewMode::ChangePriorityTask => match key.code {
                             Enter => {
@@ -347,16 +573,31 @@ impl App {
                                 self.selected_priority_task_index.select(Some(0));
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
-                            Down | Char('j') => {
-                                self.next(&priority_items);
+                            Esc => {
+                                App::change_view(self, ViewMode::ViewTasks);
                             }
-                            Up | Char('k') => {
-                                self.previous(&priority_items);
+                            // The keys moving in the list of the exercises
+                            _ => match self.keys.get_action(ViewMode::ViewTasks, &key) {
+                                Some(Action::Next) => self.next(&priority_items),
+                                Some(Action::Previous) => self.previous(&priority_items),
+                                _ => {}
+                            },
+                        },
+                        ViewMode::ChangeSetsTask => match key.code {
+                            Enter => {
+                                Task::change_sets(self, &mut items, input.value());
+                                input.reset();
+
+                                App::change_view(self, ViewMode::ViewTasks);
                             }
                             Esc => {
+                                input.reset();
+
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
-                            _ => {}
+                            _ => {
+                                input.handle_event(&Event::Key(key));
+                            }
                         },
                         ViewMode::AddTask => match key.code {
                             Enter => {
@@ -371,22 +612,58 @@ impl App {
                                 input.handle_event(&Event::Key(key));
                             }
                         },
-                        ViewMode::DeleteTask => match key.code {
-                            Char('y') => {
+                        ViewMode::DeleteTask => match self.keys.get_action(self.view_mode, &key) {
+                            Some(Action::Confirm) => {
                                 Task::delete(self, &mut items);
                                 self.selected_task_index.select_previous();
 
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
-                            Char('n') => {
+                            Some(Action::Cancel) => {
                                 App::change_view(self, ViewMode::ViewTasks);
                             }
                             _ => {}
                         },
 
-                        ViewMode::InfoMigration => {
+                        ViewMode::ViewGrid => match self.keys.get_action(self.view_mode, &key) {
+                            Some(Action::Open) => {
+                                self.open_grid_day(&mut items);
+                            }
+                            Some(Action::Back) => {
+                                App::change_view(self, ViewMode::ViewProjects);
+                            }
+                            Some(Action::PreviousWeek) => self.move_grid_cursor(-7),
+                            Some(Action::NextWeek) => self.move_grid_cursor(7),
+                            Some(Action::PreviousDay) => self.move_grid_cursor(-1),
+                            Some(Action::NextDay) => self.move_grid_cursor(1),
+                            Some(Action::GridFilter) => self.next_grid_filter(),
+                            Some(Action::Help) => self.show_keys = true,
+                            Some(Action::Quit) => {
+                                return Ok(());
+                            }
+                            _ => {}
+                        },
+                        ViewMode::InfoMigration | ViewMode::InfoReadOnly => {
                             App::change_view(self, ViewMode::ViewProjects);
                         }
+
+                        ViewMode::ConflictExternalChange => {
+                            match self.keys.get_action(self.view_mode, &key) {
+                                // Apply the edit over the data changed outside
+                                Some(Action::Overwrite) => {
+                                    replay = self.overwrite_conflict(&mut items);
+                                }
+                                // Discard the edit, the data is reloaded at the next loop
+                                Some(Action::Reload) => {
+                                    if let Some((mode, _)) = self.conflict.take() {
+                                        input.reset();
+
+                                        App::change_view(self, App::get_idle_view(mode));
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
                     }
                 }
             }
@@ -404,27 +681,28 @@ impl App {
     ) {
         // defaults
         let header_area = 2;
-        let mut list_area = 73;
-        let mut grid_area = 22;
         let mut help_area = 2;
+        // The calendar has a fixed height, the list takes the rest
+        let mut grid_area = GRID_HEIGHT;
 
         if !self.config.ui.show_help {
-            list_area += help_area;
             help_area = 0;
-        } else if !self.config.ui.show_grid_activity {
-            list_area += grid_area;
+        }
+
+        if !self.config.ui.show_grid_activity {
             grid_area = 0;
         }
 
         let layout = Layout::vertical([
             Constraint::Percentage(header_area),
-            Constraint::Percentage(list_area),
-            Constraint::Percentage(grid_area),
+            Constraint::Fill(1),
+            Constraint::Length(grid_area),
             // Space for the footer helper
             Constraint::Percentage(help_area),
         ]);
 
         let [header_area, rest_area, grid_activity_area, footer_area] = layout.areas(area);
+        self.grid_activity_area = grid_activity_area;
 
         View::show_items(self, items, f, rest_area);
 
@@ -432,6 +710,14 @@ impl App {
             View::show_migration_info_modal(f, area);
         }
 
+        if self.view_mode == ViewMode::InfoReadOnly {
+            View::show_read_only_info_modal(f, area);
+        }
+
+        if self.view_mode == ViewMode::ConflictExternalChange {
+            View::show_conflict_modal(f, area);
+        }
+
         if self.view_mode == ViewMode::AddTask || self.view_mode == ViewMode::AddProject {
             View::show_new_item_modal(f, area, input)
         }
@@ -440,6 +726,15 @@ impl App {
             View::show_rename_item_modal(f, area, input)
         }
 
+        if self.view_mode == ViewMode::ChangeDayOffProject {
+            View::show_day_off_modal(f, area, input)
+        }
+
+        if self.view_mode == ViewMode::ChangeSetsTask {
+            let kind = Task::get_current(self).kind;
+            View::show_sets_task_modal(f, area, input, kind)
+        }
+
         if self.view_mode == ViewMode::DeleteTask || self.view_mode == ViewMode::DeleteProject {
             View::show_delete_item_modal(self, f, area)
         }
@@ -452,11 +747,25 @@ impl App {
             View::show_select_task_priority_modal(self, priority_items, f, area)
         }
 
-        f.render_widget(
-            Paragraph::new(format!("::{}::", env!("CARGO_PKG_NAME"))).centered(),
-            header_area,
+        let streaks = Stats::get_streaks(
+            &self.projects,
+            self.config.streak.threshold as f32,
+            Local::now().date_naive(),
+        );
+
+        let mut header = format!(
+            "::{}:: streak {} (best {})",
+            env!("CARGO_PKG_NAME"),
+            streaks.current,
+            streaks.longest
         );
 
+        if self.read_only {
+            header.push_str(" (read-only)");
+        }
+
+        f.render_widget(Paragraph::new(header).centered(), header_area);
+
         if self.config.ui.show_grid_activity {
             View::show_grid_activity(self, f, grid_activity_area);
         }
@@ -464,6 +773,10 @@ impl App {
         if self.config.ui.show_help {
             View::show_footer_helper(self, f, footer_area)
         }
+
+        if self.show_keys {
+            View::show_keys_modal(self, f, area)
+        }
     }
 
     fn next(&mut self, items: &[ListItem]) {
@@ -500,6 +813,7 @@ impl App {
         match self.view_mode {
             ViewMode::ViewProjects
             | ViewMode::RenameProject
+            | ViewMode::ChangeDayOffProject
             | ViewMode::AddProject
             | ViewMode::DeleteProject => &mut self.selected_project_index,
 
@@ -507,14 +821,214 @@ impl App {
             ViewMode::RenameTask => &mut self.selected_task_index,
             ViewMode::ChangeStatusTask => &mut self.selected_status_task_index,
             ViewMode::ChangePriorityTask => &mut self.selected_priority_task_index,
+            ViewMode::ChangeSetsTask => &mut self.selected_task_index,
             ViewMode::AddTask => &mut self.selected_task_index,
             ViewMode::DeleteTask => &mut self.selected_task_index,
 
+            ViewMode::ViewGrid => &mut self.selected_project_index,
+
             ViewMode::InfoMigration => &mut self.selected_project_index,
+            ViewMode::InfoReadOnly => &mut self.selected_project_index,
+            ViewMode::ConflictExternalChange => match self.conflict {
+                Some((mode, _)) if App::get_idle_view(mode) == ViewMode::ViewTasks => {
+                    &mut self.selected_task_index
+                }
+                _ => &mut self.selected_project_index,
+            },
         }
     }
 
     fn change_view(&mut self, mode: ViewMode) {
         self.view_mode = mode
     }
+
+    // Views where nothing is being edited
+    fn is_idle_view(mode: ViewMode) -> bool {
+        matches!(
+            mode,
+            ViewMode::ViewProjects
+                | ViewMode::ViewTasks
+                | ViewMode::ViewGrid
+                | ViewMode::InfoMigration
+                | ViewMode::InfoReadOnly
+        )
+    }
+
+    // View to go back to when the edit is completed
+    fn get_idle_view(mode: ViewMode) -> ViewMode {
+        match mode {
+            ViewMode::RenameTask
+            | ViewMode::ChangeStatusTask
+            | ViewMode::ChangePriorityTask
+            | ViewMode::ChangeSetsTask
+            | ViewMode::AddTask
+            | ViewMode::DeleteTask => ViewMode::ViewTasks,
+            _ => ViewMode::ViewProjects,
+        }
+    }
+
+    // Key that saves the edit of the view
+    fn is_confirm_key(&self, key: &KeyEvent) -> bool {
+        match self.view_mode {
+            ViewMode::RenameProject
+            | ViewMode::ChangeDayOffProject
+            | ViewMode::AddProject
+            | ViewMode::RenameTask
+            | ViewMode::ChangeStatusTask
+            | ViewMode::ChangePriorityTask
+            | ViewMode::ChangeSetsTask
+            | ViewMode::AddTask => key.code == KeyCode::Enter,
+            ViewMode::DeleteProject | ViewMode::DeleteTask => {
+                self.keys.get_action(self.view_mode, key) == Some(Action::Confirm)
+            }
+            _ => false,
+        }
+    }
+
+    fn get_grid_activity(&self) -> GridActivity {
+        GridActivity::new(self.grid_activity_area.width, Local::now().date_naive())
+    }
+
+    fn next_grid_filter(&mut self) {
+        self.grid_filter = self
+            .grid_filter
+            .next(&self.projects, self.config.get_tags());
+    }
+
+    fn move_grid_cursor(&mut self, days: i64) {
+        let grid = self.get_grid_activity();
+        let date = self.selected_grid_date.unwrap_or(grid.today);
+
+        self.selected_grid_date = Some(grid.move_date(date, days));
+    }
+
+    // Go to the tasks of the day under the cursor, the days that don't exist are skipped
+    fn open_grid_day(&mut self, items: &mut Vec<ListItem>) {
+        let Some(date) = self.selected_grid_date else {
+            return;
+        };
+
+        let title = date.format(PROJECT_DATE_FORMAT).to_string();
+
+        let Some(index) = self.projects.iter().position(|p| p.title == title) else {
+            return;
+        };
+
+        self.selected_project_index.select(Some(index));
+        Task::load_items(self, items);
+        self.selected_task_index.select(Some(0));
+
+        App::change_view(self, ViewMode::ViewTasks);
+    }
+
+    // A click on a cell moves the cursor on it, a second click goes to the day.
+    // While the grid is browsed the cursor follows the mouse
+    fn handle_grid_mouse(&mut self, mouse: MouseEvent, items: &mut Vec<ListItem>) {
+        if !self.config.ui.show_grid_activity
+            || self.show_keys
+            || !matches!(self.view_mode, ViewMode::ViewProjects | ViewMode::ViewGrid)
+        {
+            return;
+        }
+
+        let Some(date) =
+            self.get_grid_activity()
+                .get_date_at(self.grid_activity_area, mouse.column, mouse.row)
+        else {
+            return;
+        };
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if self.view_mode == ViewMode::ViewGrid && self.selected_grid_date == Some(date) {
+                    self.open_grid_day(items);
+                    return;
+                }
+
+                self.selected_grid_date = Some(date);
+                App::change_view(self, ViewMode::ViewGrid);
+            }
+            MouseEventKind::Moved if self.view_mode == ViewMode::ViewGrid => {
+                self.selected_grid_date = Some(date);
+            }
+            _ => {}
+        }
+    }
+
+    fn get_selected_task(&self) -> Option<&Task> {
+        let project = self.projects.get(self.selected_project_index.selected()?)?;
+
+        project.tasks.get(self.selected_task_index.selected()?)
+    }
+
+    // Reload the data keeping the selected project (and task) by title,
+    // false when the project was removed outside
+    fn reload_external_change(&mut self, items: &mut Vec<ListItem>) -> bool {
+        self.external_change = false;
+
+        let selected_title = self
+            .selected_project_index
+            .selected()
+            .and_then(|i| self.projects.get(i))
+            .map(|p| p.title.clone());
+        let selected_task_title = self.get_selected_task().map(|t| t.title.clone());
+
+        self.projects = self.storage.read();
+
+        let index = self
+            .projects
+            .iter()
+            .position(|p| Some(&p.title) == selected_title.as_ref());
+
+        if self.view_mode == ViewMode::ViewTasks {
+            if let Some(index) = index {
+                // The tasks are sorted again by `load_items`, which keeps the selected title
+                let task_index = self.projects[index]
+                    .tasks
+                    .iter()
+                    .position(|t| Some(&t.title) == selected_task_title.as_ref());
+
+                self.selected_project_index.select(Some(index));
+                self.selected_task_index
+                    .select(Some(task_index.unwrap_or(0)));
+                Task::load_items(self, items);
+                return true;
+            }
+
+            // The project was removed outside
+            App::change_view(self, ViewMode::ViewProjects);
+        }
+
+        self.selected_project_index.select(Some(index.unwrap_or(0)));
+        Project::load_items(self, items);
+
+        index.is_some()
+    }
+
+    // Reload the data changed outside and return the confirm key of the edit, to apply it again
+    // over the new data. The edit is dropped when its project (or task) was removed outside
+    fn overwrite_conflict(&mut self, items: &mut Vec<ListItem>) -> Option<KeyEvent> {
+        let (mode, key) = self.conflict.take()?;
+        let idle_view = App::get_idle_view(mode);
+
+        App::change_view(self, idle_view);
+
+        let task_title = (idle_view == ViewMode::ViewTasks && mode != ViewMode::AddTask)
+            .then(|| self.get_selected_task().map(|t| t.title.clone()))
+            .flatten();
+
+        let is_project_kept = self.reload_external_change(items);
+        let is_task_kept = task_title.is_none_or(|title| {
+            self.view_mode == ViewMode::ViewTasks
+                && self.get_selected_task().is_some_and(|t| t.title == title)
+        });
+
+        if !(is_project_kept || mode == ViewMode::AddProject) || !is_task_kept {
+            return None;
+        }
+
+        App::change_view(self, mode);
+
+        Some(key)
+    }
 }
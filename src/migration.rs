@@ -1,71 +1,371 @@
-use serde_json::{
-    json, to_string, Map,
-    Value::{self},
-};
+use serde_json::{json, Map, Value};
 
-//                              sha of 0.1.0     0.2.0
-pub static JSON_VERSIONS: [&str; 2] = ["6ad96", "911fc"];
+type MigrationFn = fn(Vec<Value>) -> Vec<Value>;
+
+// Version of the json file created by 0.1.0, it has no migration
+pub static INITIAL_VERSION: &str = "6ad96";
+
+// A single change of the json structure, it can be applied (up) and reverted (down)
+pub struct MigrationStep {
+    // Version of the json file after `up`
+    pub version: &'static str,
+    pub up: MigrationFn,
+    pub down: MigrationFn,
+}
+
+// Ordered registry of the migrations, new steps go at the end
+pub static MIGRATIONS: [MigrationStep; 6] = [
+    // sha of 0.2.0
+    MigrationStep {
+        version: "911fc",
+        up: Migration::add_priority,
+        down: Migration::remove_priority,
+    },
+    // sha of 0.3.0
+    MigrationStep {
+        version: "5e0d1",
+        up: Migration::add_sets,
+        down: Migration::remove_sets,
+    },
+    MigrationStep {
+        version: "a41c7",
+        up: Migration::status_to_number,
+        down: Migration::status_to_string,
+    },
+    MigrationStep {
+        version: "c83e2",
+        up: Migration::add_kind,
+        down: Migration::remove_kind,
+    },
+    MigrationStep {
+        version: "e4b19",
+        up: Migration::add_progress,
+        down: Migration::remove_progress,
+    },
+    MigrationStep {
+        version: "f2d07",
+        up: Migration::add_day_off,
+        down: Migration::remove_day_off,
+    },
+];
 
 pub struct Migration;
 
 impl Migration {
-    pub fn get_migrations(version: &str, original_json: Vec<Value>) -> Vec<(&str, String)> {
-        // Mapper between json version and the relative migration
-        let mapper: Vec<(&str, String)> = vec![
-            ("6ad96", "".to_string()),
-            ("911fc", Migration::add_priority(original_json)),
-        ];
-
-        // The start index where the migration are picked
-        let start_index = mapper
-            .clone()
-            .into_iter()
-            .position(|(key, _val)| key == version);
+    // All the versions of the json file, from the oldest to the newest
+    pub fn get_versions() -> Vec<&'static str> {
+        let mut versions = vec![INITIAL_VERSION];
+        versions.extend(MIGRATIONS.iter().map(|m| m.version));
 
-        if start_index.is_none() {
-            return vec![];
+        versions
+    }
+
+    pub fn get_last_version() -> &'static str {
+        MIGRATIONS.last().map_or(INITIAL_VERSION, |m| m.version)
+    }
+
+    // Steps to apply to bring a json file from `version` to the last one
+    pub fn get_pending(version: &str) -> &'static [MigrationStep] {
+        if version == INITIAL_VERSION {
+            return &MIGRATIONS;
         }
 
-        let all_migrations: Vec<(&str, String)> = mapper.into_iter().collect();
+        match MIGRATIONS.iter().position(|m| m.version == version) {
+            Some(index) => &MIGRATIONS[(index + 1)..],
+            None => &[],
+        }
+    }
 
-        // Slice for pick only the useful migration
-        all_migrations[(start_index.unwrap() + 1)..].to_vec()
+    // Step to revert to bring a json file from `version` to the previous one
+    pub fn get_rollback(version: &str) -> Option<(&'static MigrationStep, &'static str)> {
+        let index = MIGRATIONS.iter().position(|m| m.version == version)?;
+
+        let previous_version = if index == 0 {
+            INITIAL_VERSION
+        } else {
+            MIGRATIONS[index - 1].version
+        };
+
+        Some((&MIGRATIONS[index], previous_version))
     }
 
-    // Migrations
-    fn add_priority(original_json: Vec<Value>) -> String {
-        let mut internal_json = original_json.clone();
-
-        let new_json: Vec<Map<String, Value>> = internal_json
-            .iter_mut()
-            .map(|p| {
-                // Get all tasks from each project and convert into "Map" type from serde
-                // in order to do some operations with the json
-                // Vec = Array ; Map = Object
-                let mut tasks = serde_json::from_value::<Vec<Map<String, Value>>>(
-                    p.get("tasks").unwrap().clone(),
-                )
-                .unwrap();
-
-                // Add to each task a new key value (i.e. {priority: 0})
-                tasks.iter_mut().for_each(|t| {
-                    // Entry and or_insert methods are used for add a new key
-                    // cf. https://docs.rs/serde_json/latest/serde_json/map/enum.Entry.html#method.or_insert
-                    t.entry("priority").or_insert(json!(0));
-                });
-
-                // Convert "p" into the "Map" type from serde in order to do some operations with the json
-                let mut project = serde_json::from_value::<Map<String, Value>>(p.clone()).unwrap();
-
-                // Replace the "tasks" key with the new one
-                // Insert method is used for replace a new with a new value
-                // cf. https://docs.rs/serde_json/latest/serde_json/map/struct.Map.html#method.insert
-                project.insert("tasks".to_string(), json!(tasks)).unwrap();
+    // Lines describing the changes between two jsons, i.e. "+ 18.10.2026 > pushups > priority: 0"
+    pub fn diff(before: &[Value], after: &[Value]) -> Vec<String> {
+        let mut lines = vec![];
+
+        Migration::diff_values(
+            "",
+            &Value::Array(before.to_vec()),
+            &Value::Array(after.to_vec()),
+            &mut lines,
+        );
+
+        lines
+    }
+
+    fn diff_values(path: &str, before: &Value, after: &Value, lines: &mut Vec<String>) {
+        let join = |key: &str| {
+            if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{path} > {key}")
+            }
+        };
+
+        match (before, after) {
+            (Value::Object(b), Value::Object(a)) => {
+                for (key, value) in b.iter() {
+                    match a.get(key) {
+                        Some(new_value) => {
+                            Migration::diff_values(&join(key), value, new_value, lines)
+                        }
+                        None => lines.push(format!("- {}: {value}", join(key))),
+                    }
+                }
+
+                for (key, value) in a.iter().filter(|(key, _)| !b.contains_key(*key)) {
+                    lines.push(format!("+ {}: {value}", join(key)))
+                }
+            }
+            (Value::Array(b), Value::Array(a)) => {
+                for index in 0..b.len().max(a.len()) {
+                    // Projects and tasks are labelled with their title
+                    let label = b
+                        .get(index)
+                        .or(a.get(index))
+                        .and_then(|v| v.get("title"))
+                        .and_then(|t| t.as_str())
+                        .map_or(format!("[{index}]"), |t| t.to_string());
+
+                    match (b.get(index), a.get(index)) {
+                        (Some(old), Some(new)) => {
+                            Migration::diff_values(&join(&label), old, new, lines)
+                        }
+                        (Some(old), None) => lines.push(format!("- {}: {old}", join(&label))),
+                        (None, Some(new)) => lines.push(format!("+ {}: {new}", join(&label))),
+                        (None, None) => {}
+                    }
+                }
+            }
+            _ => {
+                if before != after {
+                    lines.push(format!("- {path}: {before}"));
+                    lines.push(format!("+ {path}: {after}"));
+                }
+            }
+        }
+    }
+
+    // Apply `change` to every task of every project
+    fn map_tasks(original_json: Vec<Value>, change: fn(&mut Map<String, Value>)) -> Vec<Value> {
+        original_json
+            .into_iter()
+            .map(|mut project| {
+                if let Some(tasks) = project.get_mut("tasks").and_then(|t| t.as_array_mut()) {
+                    tasks
+                        .iter_mut()
+                        .filter_map(|t| t.as_object_mut())
+                        .for_each(change);
+                }
+
+                project
+            })
+            .collect()
+    }
+
+    // Apply `change` to every project
+    fn map_projects(original_json: Vec<Value>, change: fn(&mut Map<String, Value>)) -> Vec<Value> {
+        original_json
+            .into_iter()
+            .map(|mut project| {
+                if let Some(project) = project.as_object_mut() {
+                    change(project);
+                }
 
                 project
             })
-            .collect();
+            .collect()
+    }
+
+    // Migrations
+    fn add_priority(original_json: Vec<Value>) -> Vec<Value> {
+        // Entry and or_insert methods are used for add a new key (i.e. {priority: 0})
+        // cf. https://docs.rs/serde_json/latest/serde_json/map/enum.Entry.html#method.or_insert
+        Migration::map_tasks(original_json, |t| {
+            t.entry("priority").or_insert(json!(0));
+        })
+    }
+
+    fn remove_priority(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_tasks(original_json, |t| {
+            t.remove("priority");
+        })
+    }
+
+    fn add_sets(original_json: Vec<Value>) -> Vec<Value> {
+        // Add to each task the training numbers (i.e. {target_reps: 0, sets: [], weight: 0})
+        Migration::map_tasks(original_json, |t| {
+            t.entry("target_reps").or_insert(json!(0));
+            t.entry("sets").or_insert(json!([]));
+            t.entry("weight").or_insert(json!(0.0));
+        })
+    }
+
+    fn remove_sets(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_tasks(original_json, |t| {
+            t.remove("target_reps");
+            t.remove("sets");
+            t.remove("weight");
+        })
+    }
+
+    fn status_to_number(original_json: Vec<Value>) -> Vec<Value> {
+        // i.e. {status: "25"} => {status: 25}, invalid values become 0
+        Migration::map_tasks(original_json, |t| {
+            if let Some(Value::String(status)) = t.get("status") {
+                let status = status.trim().parse::<u8>().unwrap_or(0).min(100);
+                t.insert("status".to_string(), json!(status));
+            }
+        })
+    }
+
+    fn status_to_string(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_tasks(original_json, |t| {
+            if let Some(Value::Number(status)) = t.get("status") {
+                let status = status.to_string();
+                t.insert("status".to_string(), json!(status));
+            }
+        })
+    }
+
+    fn add_kind(original_json: Vec<Value>) -> Vec<Value> {
+        // The existing tasks are reps without a recorded session (i.e. {kind: "reps", track: null})
+        Migration::map_tasks(original_json, |t| {
+            t.entry("kind").or_insert(json!("reps"));
+            t.entry("track").or_insert(Value::Null);
+        })
+    }
+
+    fn remove_kind(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_tasks(original_json, |t| {
+            t.remove("kind");
+            t.remove("track");
+        })
+    }
+
+    fn add_progress(original_json: Vec<Value>) -> Vec<Value> {
+        // The target is generic (reps, seconds or km) and the recorded distance is the progress
+        // (i.e. {target_reps: 36} => {target: 36, progress: 0})
+        Migration::map_tasks(original_json, |t| {
+            if let Some(target_reps) = t.remove("target_reps") {
+                t.entry("target").or_insert(target_reps);
+            }
+
+            let progress = t
+                .get("track")
+                .and_then(|track| track.get("distance_km"))
+                .cloned()
+                .unwrap_or(json!(0));
+
+            t.entry("progress").or_insert(progress);
+        })
+    }
+
+    fn remove_progress(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_tasks(original_json, |t| {
+            if let Some(target) = t.remove("target") {
+                let target_reps = target.as_f64().unwrap_or(0.0).round() as u64;
+                t.entry("target_reps").or_insert(json!(target_reps));
+            }
+
+            t.remove("progress");
+        })
+    }
+
+    fn add_day_off(original_json: Vec<Value>) -> Vec<Value> {
+        // The existing days are training days (i.e. {day_off: null})
+        Migration::map_projects(original_json, |p| {
+            p.entry("day_off").or_insert(Value::Null);
+        })
+    }
+
+    fn remove_day_off(original_json: Vec<Value>) -> Vec<Value> {
+        Migration::map_projects(original_json, |p| {
+            p.remove("day_off");
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::from_str;
+
+    use super::*;
+
+    // The json file of each version, with the same data
+    fn get_fixture(version: &str) -> Vec<Value> {
+        let raw = match version {
+            "6ad96" => include_str!("../fixtures/migrations/6ad96.json"),
+            "911fc" => include_str!("../fixtures/migrations/911fc.json"),
+            "5e0d1" => include_str!("../fixtures/migrations/5e0d1.json"),
+            "a41c7" => include_str!("../fixtures/migrations/a41c7.json"),
+            "c83e2" => include_str!("../fixtures/migrations/c83e2.json"),
+            "e4b19" => include_str!("../fixtures/migrations/e4b19.json"),
+            "f2d07" => include_str!("../fixtures/migrations/f2d07.json"),
+            _ => panic!("missing fixture for the version {version}"),
+        };
+
+        from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn each_step_up_gives_the_fixture_of_its_version() {
+        let versions = Migration::get_versions();
+
+        for (index, step) in MIGRATIONS.iter().enumerate() {
+            let json = (step.up)(get_fixture(versions[index]));
+
+            assert_eq!(json, get_fixture(step.version), "up of {}", step.version);
+        }
+    }
+
+    #[test]
+    fn each_step_down_gives_the_fixture_of_the_previous_version() {
+        for step in MIGRATIONS.iter() {
+            let (rollback, previous_version) = Migration::get_rollback(step.version).unwrap();
+            let json = (rollback.down)(get_fixture(step.version));
+
+            assert_eq!(
+                json,
+                get_fixture(previous_version),
+                "down of {}",
+                step.version
+            );
+        }
+    }
+
+    #[test]
+    fn pending_steps_go_from_the_version_to_the_last_one() {
+        let mut json = get_fixture(INITIAL_VERSION);
+
+        for step in Migration::get_pending(INITIAL_VERSION) {
+            json = (step.up)(json);
+        }
+
+        assert_eq!(json, get_fixture(Migration::get_last_version()));
+        assert!(Migration::get_pending(Migration::get_last_version()).is_empty());
+        assert!(Migration::get_pending("unknown").is_empty());
+        assert!(Migration::get_rollback(INITIAL_VERSION).is_none());
+    }
+
+    #[test]
+    fn diff_lists_the_added_and_removed_keys() {
+        let before = get_fixture("6ad96");
+        let after = get_fixture("911fc");
+
+        let lines = Migration::diff(&before, &after);
 
-        to_string(&new_json).unwrap()
+        assert!(lines.contains(&"+ 12.10.2026 > tasks > pushups > priority: 0".to_string()));
+        assert!(Migration::diff(&after, &after).is_empty());
     }
 }
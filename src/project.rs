@@ -1,32 +1,119 @@
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use ratatui::{
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::ListItem,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    json::Json,
-    task::{Task, TASK_ITEMS_PE, TASK_STATUS_DONE, TASK_STATUS_ZERO},
+    task::{Task, TASK_PRIORITIES, TASK_STATUS_ZERO},
     App,
 };
 
+pub const PROJECT_DATE_FORMAT: &str = "%d.%m.%Y";
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DayOffReason {
+    Rest,
+    Sick,
+    Travel,
+}
+
+impl DayOffReason {
+    pub const ALL: [DayOffReason; 3] =
+        [DayOffReason::Rest, DayOffReason::Sick, DayOffReason::Travel];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DayOffReason::Rest => "rest",
+            DayOffReason::Sick => "sick",
+            DayOffReason::Travel => "travel",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<DayOffReason> {
+        DayOffReason::ALL
+            .into_iter()
+            .find(|r| r.get_name().eq_ignore_ascii_case(name))
+    }
+}
+
+// Day deliberately without training, it is left out of the completion stats
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DayOff {
+    pub reason: DayOffReason,
+    // Empty without a note
+    pub note: String,
+}
+
+impl DayOff {
+    // i.e. "sick: flu", "rest"
+    pub fn get_repr(&self) -> String {
+        if self.note.is_empty() {
+            self.reason.get_name().to_string()
+        } else {
+            format!("{}: {}", self.reason.get_name(), self.note)
+        }
+    }
+
+    // Inverse of `get_repr`, an empty value clears the day off
+    pub fn parse(value: &str) -> Result<Option<DayOff>, String> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        let (name, note) = match value.split_once([':', ' ']) {
+            Some((name, note)) => (name, note.trim()),
+            None => (value, ""),
+        };
+
+        match DayOffReason::parse(name.trim()) {
+            Some(reason) => Ok(Some(DayOff {
+                reason,
+                note: note.to_string(),
+            })),
+            None => Err(format!(
+                "invalid reason \"{name}\", expected rest, sick or travel"
+            )),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Project {
     pub title: String,
     pub tasks: Vec<Task>,
+    pub day_off: Option<DayOff>,
 }
 
 impl Project {
-    fn get_indicator_done_tasks_color(percentage: usize) -> ratatui::prelude::Color {
-        match percentage {
-            0 => Color::DarkGray,
-            p if (25..=49).contains(&p) => Color::LightMagenta,
-            p if (50..99).contains(&p) => Color::LightYellow,
-            100 => Color::LightGreen,
-            _ => Color::White,
-        }
+    pub fn sort_by_date(projects: Vec<Project>) -> Vec<Project> {
+        // Parse dates and handle invalid dates gracefully
+        let mut projects_with_dates: Vec<_> = projects
+            .into_iter()
+            .map(|p| {
+                // Attempt to parse the title as a date
+                let date = NaiveDate::parse_from_str(&p.title, PROJECT_DATE_FORMAT).ok();
+                (p, date)
+            })
+            .collect();
+
+        // Sort projects by date (projects with invalid dates will be placed first)
+        projects_with_dates.sort_by(|(_, date1), (_, date2)| {
+            match (date1, date2) {
+                (Some(d1), Some(d2)) => d1.cmp(d2), // Both dates are valid, compare them
+                (Some(_), None) => std::cmp::Ordering::Greater, // Valid dates come after invalid dates
+                (None, Some(_)) => std::cmp::Ordering::Less, // Invalid dates come before valid dates
+                (None, None) => std::cmp::Ordering::Equal, // Both dates are invalid, keep their order
+            }
+        });
+
+        // Extract the sorted projects (discard the dates)
+        projects_with_dates.into_iter().map(|(p, _)| p).collect()
     }
 
     pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
@@ -38,7 +125,7 @@ impl Project {
             let done_tasks: Vec<Task> = tasks
                 .clone()
                 .into_iter()
-                .filter(|t| t.status == TASK_STATUS_DONE)
+                .filter(|t| t.status.is_done())
                 .collect();
 
             let percentage = if tasks.is_empty() {
@@ -47,12 +134,25 @@ impl Project {
                 (done_tasks.len() * 100) / tasks.len()
             };
 
-            let mut lines = vec![Line::from(vec![
-                Span::raw(format!("[{}/{}] ", done_tasks.len(), tasks.len(),)).style(
-                    Style::default().fg(Project::get_indicator_done_tasks_color(percentage)),
-                ),
-                Span::raw(project.title.clone()),
-            ])];
+            // A day off shows its reason and note instead of the done tasks
+            let mut lines = match &project.day_off {
+                Some(day_off) => vec![Line::from(vec![
+                    Span::raw(format!("[{}] ", day_off.reason.get_name()))
+                        .style(Style::default().fg(app.theme.day_off)),
+                    Span::raw(project.title.clone()),
+                    Span::raw(if day_off.note.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", day_off.note)
+                    })
+                    .style(Style::default().fg(app.theme.muted)),
+                ])],
+                None => vec![Line::from(vec![
+                    Span::raw(format!("[{}/{}] ", done_tasks.len(), tasks.len(),))
+                        .style(Style::default().fg(app.theme.get_done_tasks_color(percentage))),
+                    Span::raw(project.title.clone()),
+                ])],
+            };
             lines.sort_by_key(|line| line.to_string());
 
             items.push(ListItem::from(lines));
@@ -62,12 +162,22 @@ impl Project {
     }
 
     pub fn create_all_projects(app: &mut App, items: &mut Vec<ListItem>) {
-        let format_str = "%d.%m.%Y";
+        // The days can't be saved, they are created by the session holding the lock
+        // (`create` would load the items again and again)
+        if app.read_only {
+            return;
+        }
+
+        let format_str = PROJECT_DATE_FORMAT;
 
-        let first_date_str = Project::get_first(app).clone().title.to_string();
-        let last_date_str = chrono::Local::now().format(format_str).to_string();
-        let first_date = chrono::NaiveDate::parse_from_str(&first_date_str, format_str)
-            .expect("Invalid first date format!");
+        // Projects are sorted by date, the ones with an invalid date (i.e. renamed) are skipped
+        let Some(first_date) = app
+            .projects
+            .iter()
+            .find_map(|p| NaiveDate::parse_from_str(&p.title, format_str).ok())
+        else {
+            return;
+        };
         let last_date = Local::now().naive_local().date();
 
         let mut dates = Vec::new();
@@ -81,7 +191,7 @@ impl Project {
     }
 
     pub fn reload(app: &mut App, items: &mut Vec<ListItem>) {
-        app.projects = Json::read();
+        app.projects = app.storage.read();
         Project::load_items(app, items)
     }
 
@@ -89,28 +199,45 @@ impl Project {
         &app.projects[app.selected_project_index.selected().unwrap()]
     }
 
-    pub fn get_first(app: &mut App) -> &Project {
-        &app.projects[0]
+    // Tasks of a new day: the scheduled routine for dates, otherwise the template
+    fn get_template_tasks(app: &App, title: &str) -> Vec<Task> {
+        let exercises = match NaiveDate::parse_from_str(title, PROJECT_DATE_FORMAT) {
+            Ok(date) => app.config.get_exercises_for(date),
+            Err(_) => &app.config.template.exercises,
+        };
+
+        exercises
+            .iter()
+            .map(|exercise| Task {
+                title: exercise.title.clone(),
+                status: TASK_STATUS_ZERO,
+                // Fallback to no priority if the config contains an unknown one
+                priority: if TASK_PRIORITIES.contains(&exercise.priority) {
+                    exercise.priority
+                } else {
+                    0
+                },
+                target: exercise.target,
+                progress: 0.0,
+                sets: vec![],
+                weight: 0.0,
+                kind: exercise.kind,
+                track: None,
+            })
+            .collect()
     }
 
     pub fn create(app: &mut App, items: &mut Vec<ListItem>, mut value: String) {
         if value.is_empty() {
             let now = chrono::Local::now();
-            value = now.format("%d.%m.%Y").to_string();
+            value = now.format(PROJECT_DATE_FORMAT).to_string();
         }
 
-        // create new project with items from TASK_ITEMS_PE
+        // create new project with the exercises from the config template or schedule
         let new_project = Project {
             title: value.to_string(),
-            tasks: TASK_ITEMS_PE
-                .iter()
-                .take(3)
-                .map(|&item| Task {
-                    title: item.to_string(),
-                    status: TASK_STATUS_ZERO.to_string(),
-                    priority: 0,
-                })
-                .collect(),
+            tasks: Project::get_template_tasks(app, &value),
+            day_off: None,
         };
 
         let mut internal_projects = app.projects.clone();
@@ -122,16 +249,41 @@ impl Project {
 
         internal_projects.push(new_project);
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Project::reload(app, items);
     }
 
     pub fn rename(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
+        let mut internal_projects = app.projects.clone();
+        let index = app.selected_project_index.selected().unwrap();
+
+        // duplicate case, like in `create`
+        if internal_projects
+            .iter()
+            .enumerate()
+            .any(|(i, p)| i != index && p.title == value)
+        {
+            return;
+        }
+
+        internal_projects[index].title = value.to_string();
+
+        app.storage.write(internal_projects);
+        Project::reload(app, items)
+    }
+
+    // Mark the selected day as rest, sick or travel (i.e. "sick: flu"), an empty value clears it
+    pub fn change_day_off(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
+        // Ignore the value if the reason is unknown
+        let Ok(day_off) = DayOff::parse(value) else {
+            return;
+        };
+
         let mut internal_projects = app.projects.clone();
 
-        internal_projects[app.selected_project_index.selected().unwrap()].title = value.to_string();
+        internal_projects[app.selected_project_index.selected().unwrap()].day_off = day_off;
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Project::reload(app, items)
     }
 
@@ -140,7 +292,7 @@ impl Project {
 
         internal_projects.remove(app.selected_project_index.selected().unwrap());
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Project::reload(app, items)
     }
 }
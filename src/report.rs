@@ -0,0 +1,180 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    project::{DayOffReason, Project, PROJECT_DATE_FORMAT},
+    stats::{Stats, Streaks},
+    task::{Task, TaskKind},
+    util::Util,
+};
+
+// Version of the json output of the commands, it is independent of the json file version
+// and it only changes when a field is removed or changes meaning (new fields can be added)
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+// Output of `list --format json`
+#[derive(Serialize)]
+pub struct DaysReport {
+    pub schema_version: u32,
+    pub days: Vec<DayReport>,
+}
+
+// Output of `stats --format json`
+#[derive(Serialize)]
+pub struct StatsReport {
+    pub schema_version: u32,
+    pub days: usize,
+    // Mean completion of the days with exercises, null without them
+    pub completion: Option<f32>,
+    pub streak: StreakReport,
+    pub history: Vec<HistoryReport>,
+}
+
+#[derive(Serialize)]
+pub struct DayReport {
+    // The day title as shown in the TUI (i.e. "18.10.2026")
+    pub title: String,
+    // ISO 8601 date (i.e. "2026-10-18"), null when the title is not a date
+    pub date: Option<String>,
+    // Null for the days without exercises and the days off
+    pub completion: Option<f32>,
+    pub tasks: Vec<TaskReport>,
+    // Rest, sick or travel day, null for a training day
+    pub day_off: Option<DayOffReport>,
+}
+
+#[derive(Serialize)]
+pub struct DayOffReport {
+    // "rest", "sick" or "travel"
+    pub reason: DayOffReason,
+    pub note: String,
+}
+
+#[derive(Serialize)]
+pub struct TaskReport {
+    pub title: String,
+    // 0 to 100
+    pub status: u8,
+    pub done: bool,
+    // 0 when there is no priority, 1 highest, 3 lowest
+    pub priority: u8,
+    // i.e. "!!!"
+    pub priority_indicator: String,
+    // Target of the reps kind, 0 for the other kinds
+    pub target_reps: u32,
+    // Reps, seconds or kilometres depending on the kind
+    pub target: f32,
+    // Seconds or kilometres done, the reps are the sum of the sets
+    pub progress: f32,
+    // "", "s" or "km"
+    pub unit: String,
+    pub sets: Vec<u32>,
+    pub weight: f32,
+    // "reps", "duration" or "distance"
+    pub kind: TaskKind,
+    // Recorded session (i.e. imported from a gpx file), null without it
+    pub track: Option<TrackReport>,
+}
+
+#[derive(Serialize)]
+pub struct TrackReport {
+    // RFC 3339 time of the start
+    pub start: String,
+    pub distance_km: f64,
+    pub duration_s: u64,
+    pub elevation_gain_m: f64,
+}
+
+#[derive(Serialize)]
+pub struct StreakReport {
+    pub current: u32,
+    pub longest: u32,
+}
+
+#[derive(Serialize)]
+pub struct HistoryReport {
+    pub title: String,
+    pub date: Option<String>,
+    pub completion: Option<f32>,
+}
+
+pub struct Report;
+
+impl Report {
+    fn get_iso_date(title: &str) -> Option<String> {
+        NaiveDate::parse_from_str(title, PROJECT_DATE_FORMAT)
+            .ok()
+            .map(|d| d.to_string())
+    }
+
+    fn get_task(task: &Task) -> TaskReport {
+        TaskReport {
+            title: task.title.clone(),
+            status: task.status.percentage(),
+            done: task.status.is_done(),
+            priority: task.priority,
+            priority_indicator: Util::get_priority_indicator(task.priority),
+            target_reps: if task.kind == TaskKind::Reps {
+                task.target as u32
+            } else {
+                0
+            },
+            target: task.target,
+            progress: if task.kind == TaskKind::Reps {
+                task.sets.iter().sum::<u32>() as f32
+            } else {
+                task.progress
+            },
+            unit: task.kind.get_unit().to_string(),
+            sets: task.sets.clone(),
+            weight: task.weight,
+            kind: task.kind,
+            track: task.track.as_ref().map(|t| TrackReport {
+                start: t.start.clone(),
+                distance_km: t.distance_km,
+                duration_s: t.duration_s,
+                elevation_gain_m: t.elevation_gain_m,
+            }),
+        }
+    }
+
+    pub fn get_day(project: &Project) -> DayReport {
+        DayReport {
+            title: project.title.clone(),
+            date: Report::get_iso_date(&project.title),
+            completion: Stats::get_completion(project),
+            tasks: project.tasks.iter().map(Report::get_task).collect(),
+            day_off: project.day_off.as_ref().map(|d| DayOffReport {
+                reason: d.reason,
+                note: d.note.clone(),
+            }),
+        }
+    }
+
+    pub fn get_days(projects: &[&Project]) -> DaysReport {
+        DaysReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            days: projects.iter().map(|p| Report::get_day(p)).collect(),
+        }
+    }
+
+    pub fn get_stats(projects: &[Project], streaks: Streaks) -> StatsReport {
+        StatsReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            days: projects.len(),
+            completion: Stats::get_average_completion(projects),
+            streak: StreakReport {
+                current: streaks.current,
+                longest: streaks.longest,
+            },
+            history: projects
+                .iter()
+                .map(|p| HistoryReport {
+                    title: p.title.clone(),
+                    date: Report::get_iso_date(&p.title),
+                    completion: Stats::get_completion(p),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::{
+    project::{Project, PROJECT_DATE_FORMAT},
+    task::Task,
+};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Streaks {
+    pub current: u32,
+    pub longest: u32,
+}
+
+pub struct Stats;
+
+impl Stats {
+    // Mean of the task statuses of the day, none when the day has no tasks or is a day off
+    pub fn get_completion(project: &Project) -> Option<f32> {
+        Stats::get_filtered_completion(project, |_| true)
+    }
+
+    // Like `get_completion` with only the tasks accepted by `filter`
+    pub fn get_filtered_completion(
+        project: &Project,
+        filter: impl Fn(&Task) -> bool,
+    ) -> Option<f32> {
+        if project.day_off.is_some() {
+            return None;
+        }
+
+        let statuses: Vec<f32> = project
+            .tasks
+            .iter()
+            .filter(|t| filter(t))
+            .map(|t| t.status.percentage() as f32)
+            .collect();
+
+        if statuses.is_empty() {
+            return None;
+        }
+
+        Some(statuses.iter().sum::<f32>() / statuses.len() as f32)
+    }
+
+    // Mean completion of the days with tasks
+    pub fn get_average_completion(projects: &[Project]) -> Option<f32> {
+        let completions: Vec<f32> = projects.iter().filter_map(Stats::get_completion).collect();
+
+        if completions.is_empty() {
+            return None;
+        }
+
+        Some(completions.iter().sum::<f32>() / completions.len() as f32)
+    }
+
+    // Consecutive days with a completion of at least `threshold`. The rest days (without
+    // exercises or marked as day off) are skipped, a missing day breaks the streak and today doesn't break
+    // the current streak while it is in progress
+    pub fn get_streaks(projects: &[Project], threshold: f32, today: NaiveDate) -> Streaks {
+        // Some(true) when the day meets the threshold, None for the rest days
+        let days: BTreeMap<NaiveDate, Option<bool>> = projects
+            .iter()
+            .filter_map(|p| {
+                let date = NaiveDate::parse_from_str(&p.title, PROJECT_DATE_FORMAT).ok()?;
+                let is_met = Stats::get_completion(p).map(|c| c >= threshold);
+
+                Some((date, is_met))
+            })
+            .filter(|(date, _)| *date <= today)
+            .collect();
+
+        let mut streaks = Streaks::default();
+
+        let Some(first_date) = days.keys().next().copied() else {
+            return streaks;
+        };
+
+        let mut length = 0;
+        let mut day = first_date;
+
+        while day <= today {
+            match days.get(&day) {
+                Some(Some(true)) => length += 1,
+                Some(None) => {}
+                _ if day == today => {}
+                _ => length = 0,
+            }
+
+            streaks.longest = streaks.longest.max(length);
+            day += Duration::days(1);
+        }
+
+        streaks.current = length;
+
+        streaks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{DayOff, DayOffReason};
+    use crate::task::{Status, TaskKind, TASK_STATUS_DONE, TASK_STATUS_ZERO};
+
+    fn get_project(title: &str, statuses: &[Status]) -> Project {
+        Project {
+            title: title.to_string(),
+            tasks: statuses
+                .iter()
+                .map(|s| Task {
+                    title: "pushups".to_string(),
+                    status: *s,
+                    priority: 0,
+                    target: 0.0,
+                    progress: 0.0,
+                    sets: vec![],
+                    weight: 0.0,
+                    kind: TaskKind::Reps,
+                    track: None,
+                })
+                .collect(),
+            day_off: None,
+        }
+    }
+
+    #[test]
+    fn completion_is_the_mean_of_the_statuses() {
+        let project = get_project("01.01.2026", &[TASK_STATUS_DONE, Status::new(50)]);
+
+        assert_eq!(Stats::get_completion(&project), Some(75.0));
+        assert_eq!(Stats::get_completion(&get_project("02.01.2026", &[])), None);
+    }
+
+    #[test]
+    fn streaks_count_the_consecutive_done_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+        let projects = vec![
+            get_project("01.01.2026", &[TASK_STATUS_DONE]),
+            get_project("02.01.2026", &[TASK_STATUS_DONE]),
+            get_project("03.01.2026", &[TASK_STATUS_DONE]),
+            get_project("04.01.2026", &[TASK_STATUS_ZERO]),
+            get_project("08.01.2026", &[TASK_STATUS_DONE]),
+            get_project("09.01.2026", &[TASK_STATUS_DONE]),
+            // Today is in progress
+            get_project("10.01.2026", &[TASK_STATUS_ZERO]),
+        ];
+
+        assert_eq!(
+            Stats::get_streaks(&projects, 100.0, today),
+            Streaks {
+                current: 2,
+                longest: 3
+            }
+        );
+    }
+
+    #[test]
+    fn rest_days_do_not_break_the_streak() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 4).unwrap();
+        let projects = vec![
+            get_project("01.01.2026", &[TASK_STATUS_DONE, Status::new(50)]),
+            // Rest day
+            get_project("02.01.2026", &[]),
+            get_project("03.01.2026", &[Status::new(75)]),
+            get_project("04.01.2026", &[Status::new(100)]),
+        ];
+
+        assert_eq!(
+            Stats::get_streaks(&projects, 75.0, today),
+            Streaks {
+                current: 3,
+                longest: 3
+            }
+        );
+        assert_eq!(Stats::get_streaks(&projects, 100.0, today).current, 1);
+    }
+
+    #[test]
+    fn days_off_are_left_out_of_the_completion() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
+        let mut projects = vec![
+            get_project("01.01.2026", &[TASK_STATUS_DONE]),
+            get_project("02.01.2026", &[TASK_STATUS_ZERO]),
+            get_project("03.01.2026", &[TASK_STATUS_DONE]),
+        ];
+        projects[1].day_off = Some(DayOff {
+            reason: DayOffReason::Sick,
+            note: "flu".to_string(),
+        });
+
+        assert_eq!(Stats::get_completion(&projects[1]), None);
+        assert_eq!(Stats::get_average_completion(&projects), Some(100.0));
+        assert_eq!(Stats::get_streaks(&projects, 100.0, today).current, 2);
+    }
+}
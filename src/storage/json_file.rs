@@ -0,0 +1,104 @@
+use std::{
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use crate::{json::Json, project::Project};
+
+use super::Storage;
+
+pub struct JsonFileStorage {
+    path: PathBuf,
+    // Hash of the content of the file at the last read or write, the modification time
+    // and the size miss the edits of the same second and length
+    stamp: Option<u64>,
+}
+
+impl JsonFileStorage {
+    /// Constructor for JsonFileStorage
+    pub fn new(path: PathBuf) -> Self {
+        let stamp = JsonFileStorage::get_stamp(&path);
+
+        Self { path, stamp }
+    }
+
+    fn get_stamp(path: &Path) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+
+        fs::read(path).ok()?.hash(&mut hasher);
+
+        Some(hasher.finish())
+    }
+}
+
+impl Storage for JsonFileStorage {
+    fn read(&self) -> Vec<Project> {
+        Json::read(&self.path)
+    }
+
+    fn write(&mut self, projects: Vec<Project>) {
+        Json::write(&self.path, projects);
+        self.stamp = JsonFileStorage::get_stamp(&self.path)
+    }
+
+    fn has_changed(&mut self) -> bool {
+        let stamp = JsonFileStorage::get_stamp(&self.path);
+
+        if stamp == self.stamp {
+            return false;
+        }
+
+        // A file still being written (or hand edited with errors) is checked again later
+        if Json::try_read(&self.path).is_err() {
+            return false;
+        }
+
+        self.stamp = stamp;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_project(title: &str) -> Project {
+        Project {
+            title: title.to_string(),
+            tasks: vec![],
+            day_off: None,
+        }
+    }
+
+    #[test]
+    fn only_the_changes_made_outside_are_detected() {
+        let dir = std::env::temp_dir().join(format!("basilk-json-file-{}", std::process::id()));
+        let path = dir.join("basilk.json");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut storage = JsonFileStorage::new(path.clone());
+        storage.write(vec![get_project("01.01.2026")]);
+
+        // Its own writes aren't changes
+        assert!(!storage.has_changed());
+
+        // An edit of the same length, likely within the same second
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.replace("01.01.2026", "02.01.2026")).unwrap();
+
+        assert!(storage.has_changed());
+        assert!(!storage.has_changed());
+        assert_eq!(storage.read()[0].title, "02.01.2026");
+
+        // An invalid file is ignored until it's fixed
+        fs::write(&path, "[{").unwrap();
+        assert!(!storage.has_changed());
+
+        fs::write(&path, content).unwrap();
+        assert!(storage.has_changed());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
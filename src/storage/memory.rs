@@ -0,0 +1,194 @@
+use crate::project::Project;
+
+use super::Storage;
+
+// Keeps the projects only in memory, nothing is saved on disk
+#[derive(Default)]
+pub struct MemoryStorage {
+    projects: Vec<Project>,
+}
+
+impl MemoryStorage {
+    /// Constructor for MemoryStorage
+    pub fn new(projects: Vec<Project>) -> Self {
+        Self { projects }
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self) -> Vec<Project> {
+        Project::sort_by_date(self.projects.clone())
+    }
+
+    fn write(&mut self, projects: Vec<Project>) {
+        self.projects = projects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+        widgets::ListItem,
+    };
+
+    use super::*;
+    use crate::{
+        config::Config,
+        task::{Task, TaskKind, TASK_STATUS_ZERO},
+        App, ViewMode,
+    };
+
+    fn get_project(title: &str) -> Project {
+        Project {
+            title: title.to_string(),
+            tasks: vec![],
+            day_off: None,
+        }
+    }
+
+    fn get_task(title: &str) -> Task {
+        Task {
+            title: title.to_string(),
+            status: TASK_STATUS_ZERO,
+            priority: 0,
+            target: 0.0,
+            progress: 0.0,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Reps,
+            track: None,
+        }
+    }
+
+    // The day "02.01.2026" with the tasks "a", "b" and "c", the task "b" being selected
+    fn get_app_with_selected_task(items: &mut Vec<ListItem>) -> App {
+        let mut project = get_project("02.01.2026");
+        project.tasks = vec![get_task("a"), get_task("b"), get_task("c")];
+
+        let mut app = App::setup(
+            Config::get_default(),
+            Box::new(MemoryStorage::new(vec![project])),
+        );
+
+        app.view_mode = ViewMode::ViewTasks;
+        app.selected_task_index.select(Some(1));
+        Task::load_items(&mut app, items);
+
+        app
+    }
+
+    fn get_selected_task_title(app: &App) -> &str {
+        &app.projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()]
+        .title
+    }
+
+    #[test]
+    fn read_sorts_projects_by_date() {
+        let storage = MemoryStorage::new(vec![
+            get_project("02.01.2026"),
+            get_project("01.01.2026"),
+            get_project("not a date"),
+        ]);
+
+        let titles: Vec<String> = storage.read().into_iter().map(|p| p.title).collect();
+
+        assert_eq!(titles, ["not a date", "01.01.2026", "02.01.2026"]);
+    }
+
+    #[test]
+    fn project_and_task_changes_are_written_into_the_storage() {
+        let mut app = App::setup(Config::get_default(), Box::new(MemoryStorage::default()));
+        let mut items: Vec<ListItem> = vec![];
+
+        // An empty title creates the project of today from the template
+        Project::create(&mut app, &mut items, "".to_string());
+
+        let projects = app.storage.read();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].tasks.len(), 3);
+
+        Task::create(&mut app, &mut items, "plank");
+
+        let projects = app.storage.read();
+        assert!(projects[0].tasks.iter().any(|t| t.title == "plank"));
+    }
+
+    #[test]
+    fn external_change_is_reloaded_keeping_the_selection() {
+        let mut items: Vec<ListItem> = vec![];
+        let mut app = get_app_with_selected_task(&mut items);
+
+        // A day before the selected one and a task before the selected one are added outside
+        let mut projects = app.storage.read();
+        projects[0].tasks.insert(0, get_task("z"));
+        projects.push(get_project("01.01.2026"));
+        app.storage.write(projects);
+
+        assert!(app.reload_external_change(&mut items));
+        assert_eq!(app.view_mode, ViewMode::ViewTasks);
+        assert_eq!(
+            app.projects[app.selected_project_index.selected().unwrap()].title,
+            "02.01.2026"
+        );
+        assert_eq!(get_selected_task_title(&app), "b");
+
+        // The selected day is removed outside
+        app.storage.write(vec![get_project("01.01.2026")]);
+
+        assert!(!app.reload_external_change(&mut items));
+        assert_eq!(app.view_mode, ViewMode::ViewProjects);
+    }
+
+    #[test]
+    fn overwritten_conflict_keeps_the_external_change() {
+        let mut items: Vec<ListItem> = vec![];
+        let mut app = get_app_with_selected_task(&mut items);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        // The task "c" is renamed outside while "b" is being renamed here
+        let mut projects = app.storage.read();
+        projects[0].tasks[2].title = "c2".to_string();
+        projects[0].tasks.insert(0, get_task("z"));
+        app.storage.write(projects);
+
+        app.external_change = true;
+        app.conflict = Some((ViewMode::RenameTask, enter));
+        app.view_mode = ViewMode::ConflictExternalChange;
+
+        assert_eq!(app.overwrite_conflict(&mut items), Some(enter));
+        assert_eq!(app.view_mode, ViewMode::RenameTask);
+        assert_eq!(get_selected_task_title(&app), "b");
+
+        // The edit is applied again over the new data
+        Task::rename(&mut app, &mut items, "b2");
+
+        let mut titles: Vec<String> = app.storage.read()[0]
+            .tasks
+            .iter()
+            .map(|t| t.title.clone())
+            .collect();
+        titles.sort();
+
+        assert_eq!(titles, ["a", "b2", "c2", "z"]);
+
+        // The edit is dropped when its task was removed outside
+        let mut projects = app.storage.read();
+        projects[0].tasks.retain(|t| t.title != "b2");
+        app.storage.write(projects);
+
+        app.selected_task_index.select(Some(
+            app.projects[0]
+                .tasks
+                .iter()
+                .position(|t| t.title == "b2")
+                .unwrap(),
+        ));
+        app.conflict = Some((ViewMode::RenameTask, enter));
+        app.view_mode = ViewMode::ConflictExternalChange;
+
+        assert_eq!(app.overwrite_conflict(&mut items), None);
+        assert_eq!(app.view_mode, ViewMode::ViewTasks);
+    }
+}
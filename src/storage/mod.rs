@@ -0,0 +1,61 @@
+use std::error::Error;
+
+use crate::{config::Backend, json::Json, project::Project};
+
+pub mod json_file;
+// Only used by the tests
+#[cfg(test)]
+pub mod memory;
+pub mod read_only;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+// Backend where the projects are saved
+pub trait Storage {
+    // Projects sorted by date
+    fn read(&self) -> Vec<Project>;
+    fn write(&mut self, projects: Vec<Project>);
+
+    // True if the data was changed outside of this session since the last check
+    fn has_changed(&mut self) -> bool {
+        false
+    }
+}
+
+// Copy the data of the backend into the backups directory, keeping the last `keep` copies
+pub fn backup(backend: &Backend, keep: usize) -> Result<(), Box<dyn Error>> {
+    match backend {
+        Backend::Json => {
+            Json::backup()?;
+            Json::prune_backups(keep)?;
+        }
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite => {
+            sqlite::SqliteStorage::backup(keep)?;
+        }
+        // `open` refuses it
+        #[cfg(not(feature = "sqlite"))]
+        Backend::Sqlite => {}
+    }
+
+    Ok(())
+}
+
+// Open the backend selected in the config
+pub fn open(backend: &Backend) -> Result<Box<dyn Storage>, Box<dyn Error>> {
+    match backend {
+        Backend::Json => Ok(Box::new(json_file::JsonFileStorage::new(
+            Json::get_current_path(),
+        ))),
+        #[cfg(feature = "sqlite")]
+        Backend::Sqlite => Ok(Box::new(sqlite::SqliteStorage::open(
+            &sqlite::SqliteStorage::get_path(),
+        )?)),
+        #[cfg(not(feature = "sqlite"))]
+        Backend::Sqlite => Err(format!(
+            "the sqlite backend is not available, build {} with the \"sqlite\" feature",
+            env!("CARGO_PKG_NAME")
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,70 @@
+use crate::project::Project;
+
+use super::Storage;
+
+// Storage of a session without the lock: the data is read from the backend (and reloaded
+// when the session holding the lock saves it) but the changes are refused
+pub struct ReadOnlyStorage {
+    storage: Box<dyn Storage>,
+}
+
+impl ReadOnlyStorage {
+    /// Constructor for ReadOnlyStorage
+    pub fn new(storage: Box<dyn Storage>) -> Self {
+        Self { storage }
+    }
+}
+
+impl Storage for ReadOnlyStorage {
+    fn read(&self) -> Vec<Project> {
+        self.storage.read()
+    }
+
+    fn write(&mut self, _projects: Vec<Project>) {}
+
+    fn has_changed(&mut self) -> bool {
+        self.storage.has_changed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::widgets::ListItem;
+
+    use super::*;
+    use crate::{config::Config, storage::memory::MemoryStorage, App};
+
+    #[test]
+    fn changes_are_refused() {
+        let project = Project {
+            title: "01.01.2026".to_string(),
+            tasks: vec![],
+            day_off: None,
+        };
+        let mut storage = ReadOnlyStorage::new(Box::new(MemoryStorage::new(vec![project])));
+
+        storage.write(vec![]);
+
+        assert_eq!(storage.read().len(), 1);
+    }
+
+    #[test]
+    fn missing_days_are_not_created() {
+        let project = Project {
+            title: "01.01.2026".to_string(),
+            tasks: vec![],
+            day_off: None,
+        };
+        let storage = ReadOnlyStorage::new(Box::new(MemoryStorage::new(vec![project])));
+
+        let mut app = App::setup(Config::get_default(), Box::new(storage));
+        app.read_only = true;
+
+        // The days up to today are missing, they would be created again at each reload
+        let mut items: Vec<ListItem> = vec![];
+        Project::load_items(&mut app, &mut items);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(app.projects.len(), 1);
+    }
+}
@@ -0,0 +1,676 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    json::Json,
+    project::{DayOff, DayOffReason, Project, PROJECT_DATE_FORMAT},
+    task::{Status, Task, TaskKind, Track},
+};
+
+use super::Storage;
+
+// `date` is the parsed title (when valid) and it is only used to sort the days.
+// The status history is bound to the day and the title of the task
+// because the tasks of a day are rewritten when it changes
+static SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS days (
+        id INTEGER PRIMARY KEY,
+        title TEXT NOT NULL UNIQUE,
+        date TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        day_id INTEGER NOT NULL REFERENCES days(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        status INTEGER NOT NULL,
+        priority INTEGER NOT NULL,
+        target_reps INTEGER NOT NULL,
+        weight REAL NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS sets (
+        task_id INTEGER NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        reps INTEGER NOT NULL,
+        PRIMARY KEY (task_id, position)
+    );
+
+    CREATE TABLE IF NOT EXISTS status_history (
+        id INTEGER PRIMARY KEY,
+        day_id INTEGER NOT NULL REFERENCES days(id) ON DELETE CASCADE,
+        task_title TEXT NOT NULL,
+        status INTEGER NOT NULL,
+        changed_at TEXT NOT NULL
+    );
+";
+
+// Changes of the schema after its creation, the index + 1 is stored in `user_version`
+static SCHEMA_MIGRATIONS: [&str; 3] = [
+    "
+    ALTER TABLE tasks ADD COLUMN kind TEXT NOT NULL DEFAULT 'reps';
+    ALTER TABLE tasks ADD COLUMN track_start TEXT;
+    ALTER TABLE tasks ADD COLUMN distance_km REAL;
+    ALTER TABLE tasks ADD COLUMN duration_s INTEGER;
+    ALTER TABLE tasks ADD COLUMN elevation_gain_m REAL;
+    ",
+    // `target_reps` is kept up to date for the older versions of the application
+    "
+    ALTER TABLE tasks ADD COLUMN target REAL NOT NULL DEFAULT 0;
+    ALTER TABLE tasks ADD COLUMN progress REAL NOT NULL DEFAULT 0;
+    UPDATE tasks SET target = target_reps;
+    UPDATE tasks SET progress = distance_km WHERE distance_km IS NOT NULL;
+    ",
+    // The note is NULL when the day is not a day off
+    "
+    ALTER TABLE days ADD COLUMN day_off TEXT;
+    ALTER TABLE days ADD COLUMN day_off_note TEXT;
+    ",
+];
+
+static DB_FILE_NAME: &str = env!("CARGO_PKG_NAME");
+
+// Days of the database by title, with the `data_version` of when they were read
+type Snapshot = (i64, HashMap<String, Project>);
+
+pub struct SqliteStorage {
+    connection: Connection,
+    // Changes when another connection commits into the database
+    data_version: i64,
+    // Days as they were last read or written, only the days that differ from them are written
+    snapshot: RefCell<Option<Snapshot>>,
+}
+
+impl SqliteStorage {
+    pub fn get_path() -> PathBuf {
+        let mut path = Json::get_dir_path();
+        path.push(format!("{DB_FILE_NAME}.sqlite"));
+
+        path
+    }
+
+    /// Constructor for SqliteStorage, the schema is created if missing
+    pub fn open(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(SqliteStorage::from_connection(Connection::open(path)?)?)
+    }
+
+    fn from_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        SqliteStorage::migrate(&connection)?;
+
+        let data_version = SqliteStorage::get_data_version(&connection)?;
+
+        Ok(Self {
+            connection,
+            data_version,
+            snapshot: RefCell::new(None),
+        })
+    }
+
+    fn migrate(connection: &Connection) -> rusqlite::Result<()> {
+        let user_version: usize =
+            connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        for (index, migration) in SCHEMA_MIGRATIONS.iter().enumerate().skip(user_version) {
+            connection.execute_batch(&format!(
+                "BEGIN; {migration} PRAGMA user_version = {}; COMMIT;",
+                index + 1
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    // Copy the database into the backups directory (next to the json ones), nothing to do
+    // before its creation. `VACUUM INTO` gives a consistent copy even while it's open
+    pub fn backup(keep: usize) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        let path = SqliteStorage::get_path();
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let dir = Json::get_backups_dir_path();
+        fs::create_dir_all(&dir)?;
+
+        let backup_path = Json::get_backup_path(
+            &dir,
+            &format!("{DB_FILE_NAME}.sqlite"),
+            Local::now().naive_local(),
+        );
+
+        Connection::open(&path)?.execute(
+            "VACUUM INTO ?1",
+            [backup_path.to_string_lossy().to_string()],
+        )?;
+        Json::prune_backups_in(&dir, keep, ".sqlite")?;
+
+        Ok(Some(backup_path))
+    }
+
+    fn get_data_version(connection: &Connection) -> rusqlite::Result<i64> {
+        connection.query_row("PRAGMA data_version", [], |row| row.get(0))
+    }
+
+    // The kind is stored with its json name
+    fn get_kind_name(kind: TaskKind) -> String {
+        serde_json::to_value(kind)
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_default()
+    }
+
+    fn parse_kind(name: &str) -> TaskKind {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).unwrap_or_default()
+    }
+
+    fn read_projects(&self) -> rusqlite::Result<Vec<Project>> {
+        // Days with an invalid date come first, like in the json file
+        let mut days_stmt = self.connection.prepare(
+            "SELECT id, title, day_off, day_off_note FROM days
+                ORDER BY date IS NOT NULL, date, id",
+        )?;
+        let mut tasks_stmt = self.connection.prepare(
+            // Databases created before the typed status have a TEXT column
+            "SELECT id, title, CAST(status AS INTEGER), priority, target, weight,
+            kind, track_start, distance_km, duration_s, elevation_gain_m, progress
+            FROM tasks WHERE day_id = ?1 ORDER BY position",
+        )?;
+        let mut sets_stmt = self
+            .connection
+            .prepare("SELECT reps FROM sets WHERE task_id = ?1 ORDER BY position")?;
+
+        let days = days_stmt
+            .query_map([], |row| {
+                let day_off = row
+                    .get::<_, Option<String>>(2)?
+                    .and_then(|name| DayOffReason::parse(&name));
+                let note = row.get::<_, Option<String>>(3)?.unwrap_or_default();
+
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    day_off.map(|reason| DayOff { reason, note }),
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut projects = Vec::with_capacity(days.len());
+
+        for (day_id, title, day_off) in days {
+            let tasks = tasks_stmt
+                .query_map([day_id], |row| {
+                    let track = match row.get::<_, Option<String>>(7)? {
+                        Some(start) => Some(Track {
+                            start,
+                            distance_km: row.get(8)?,
+                            duration_s: row.get(9)?,
+                            elevation_gain_m: row.get(10)?,
+                        }),
+                        None => None,
+                    };
+
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        Task {
+                            title: row.get(1)?,
+                            status: Status::new(row.get(2)?),
+                            priority: row.get(3)?,
+                            target: row.get(4)?,
+                            progress: row.get(11)?,
+                            sets: vec![],
+                            weight: row.get(5)?,
+                            kind: SqliteStorage::parse_kind(&row.get::<_, String>(6)?),
+                            track,
+                        },
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let mut project = Project {
+                title,
+                tasks: Vec::with_capacity(tasks.len()),
+                day_off,
+            };
+
+            for (task_id, mut task) in tasks {
+                task.sets = sets_stmt
+                    .query_map([task_id], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<u32>>>()?;
+
+                project.tasks.push(task);
+            }
+
+            projects.push(project);
+        }
+
+        Ok(projects)
+    }
+
+    fn get_days_by_title(projects: &[Project]) -> HashMap<String, Project> {
+        projects
+            .iter()
+            .map(|p| (p.title.clone(), p.clone()))
+            .collect()
+    }
+
+    // Replace the days of the database with `projects`, the title identifies a day
+    fn write_projects(&mut self, projects: &[Project]) -> Result<(), Box<dyn Error>> {
+        // Two days with the same title would be merged into one
+        let mut titles = HashSet::new();
+
+        if let Some(project) = projects.iter().find(|p| !titles.insert(p.title.as_str())) {
+            return Err(format!("the day \"{}\" is duplicated", project.title).into());
+        }
+
+        let data_version = SqliteStorage::get_data_version(&self.connection)?;
+
+        let mut snapshot = match self.snapshot.take() {
+            Some((version, days)) if version == data_version => days,
+            // Never read or changed by another connection since then
+            _ => SqliteStorage::get_days_by_title(&self.read_projects()?),
+        };
+
+        let transaction = self.connection.transaction()?;
+        let changed_at = Local::now().to_rfc3339();
+
+        // A renamed day keeps its row, so its status history
+        if let Some((old_title, title)) = SqliteStorage::get_renamed_day(&snapshot, projects) {
+            transaction.execute(
+                "UPDATE days SET title = ?1, date = ?2 WHERE title = ?3",
+                params![title, SqliteStorage::get_date(&title), old_title],
+            )?;
+
+            if let Some(project) = snapshot.remove(&old_title) {
+                snapshot.insert(title, project);
+            }
+        }
+
+        for project in projects {
+            let old_project = snapshot.get(&project.title);
+
+            if old_project
+                .is_some_and(|old| old.tasks == project.tasks && old.day_off == project.day_off)
+            {
+                continue;
+            }
+
+            SqliteStorage::write_day(&transaction, project, old_project, &changed_at)?;
+        }
+
+        // Remove the deleted days (with their tasks and history)
+        for title in snapshot.keys() {
+            if !titles.contains(title.as_str()) {
+                transaction.execute("DELETE FROM days WHERE title = ?1", [title])?;
+            }
+        }
+
+        transaction.commit()?;
+
+        *self.snapshot.get_mut() = Some((data_version, SqliteStorage::get_days_by_title(projects)));
+
+        Ok(())
+    }
+
+    // The only removed day and the only added one, when they are the same apart from the title
+    fn get_renamed_day(
+        snapshot: &HashMap<String, Project>,
+        projects: &[Project],
+    ) -> Option<(String, String)> {
+        let titles: HashSet<&str> = projects.iter().map(|p| p.title.as_str()).collect();
+
+        let removed: Vec<&Project> = snapshot
+            .values()
+            .filter(|p| !titles.contains(p.title.as_str()))
+            .collect();
+        let added: Vec<&Project> = projects
+            .iter()
+            .filter(|p| !snapshot.contains_key(&p.title))
+            .collect();
+
+        match (removed.as_slice(), added.as_slice()) {
+            ([old], [new]) if old.tasks == new.tasks && old.day_off == new.day_off => {
+                Some((old.title.clone(), new.title.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    // Parsed title used to sort the days
+    fn get_date(title: &str) -> Option<String> {
+        NaiveDate::parse_from_str(title, PROJECT_DATE_FORMAT)
+            .ok()
+            .map(|d| d.to_string())
+    }
+
+    // Write the day and all its tasks, the statuses that differ from `old_project` go in the history
+    fn write_day(
+        connection: &Connection,
+        project: &Project,
+        old_project: Option<&Project>,
+        changed_at: &str,
+    ) -> rusqlite::Result<()> {
+        let existing_day_id: Option<i64> = connection
+            .query_row(
+                "SELECT id FROM days WHERE title = ?1",
+                [&project.title],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let day_id = match existing_day_id {
+            Some(id) => id,
+            None => {
+                connection.execute(
+                    "INSERT INTO days (title, date) VALUES (?1, ?2)",
+                    params![project.title, SqliteStorage::get_date(&project.title)],
+                )?;
+                connection.last_insert_rowid()
+            }
+        };
+
+        connection.execute(
+            "UPDATE days SET day_off = ?1, day_off_note = ?2 WHERE id = ?3",
+            params![
+                project.day_off.as_ref().map(|d| d.reason.get_name()),
+                project.day_off.as_ref().map(|d| &d.note),
+                day_id
+            ],
+        )?;
+        connection.execute("DELETE FROM tasks WHERE day_id = ?1", [day_id])?;
+
+        // Last known status of each task
+        let last_statuses: HashMap<&str, Status> = old_project
+            .map(|p| {
+                p.tasks
+                    .iter()
+                    .map(|t| (t.title.as_str(), t.status))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (position, task) in project.tasks.iter().enumerate() {
+            connection.execute(
+                "INSERT INTO tasks (day_id, position, title, status, priority, target_reps, weight,
+                kind, track_start, distance_km, duration_s, elevation_gain_m, target, progress)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    day_id,
+                    position,
+                    task.title,
+                    task.status.percentage(),
+                    task.priority,
+                    task.target.round() as u32,
+                    task.weight,
+                    SqliteStorage::get_kind_name(task.kind),
+                    task.track.as_ref().map(|t| &t.start),
+                    task.track.as_ref().map(|t| t.distance_km),
+                    task.track.as_ref().map(|t| t.duration_s),
+                    task.track.as_ref().map(|t| t.elevation_gain_m),
+                    task.target,
+                    task.progress
+                ],
+            )?;
+            let task_id = connection.last_insert_rowid();
+
+            for (set_position, reps) in task.sets.iter().enumerate() {
+                connection.execute(
+                    "INSERT INTO sets (task_id, position, reps) VALUES (?1, ?2, ?3)",
+                    params![task_id, set_position, reps],
+                )?;
+            }
+
+            if last_statuses.get(task.title.as_str()) != Some(&task.status) {
+                connection.execute(
+                    "INSERT INTO status_history (day_id, task_title, status, changed_at)
+                    VALUES (?1, ?2, ?3, ?4)",
+                    params![day_id, task.title, task.status.percentage(), changed_at],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn read(&self) -> Vec<Project> {
+        let projects = self
+            .read_projects()
+            .expect("Unable to read the sqlite database");
+
+        if let Ok(data_version) = SqliteStorage::get_data_version(&self.connection) {
+            *self.snapshot.borrow_mut() =
+                Some((data_version, SqliteStorage::get_days_by_title(&projects)));
+        }
+
+        projects
+    }
+
+    fn write(&mut self, projects: Vec<Project>) {
+        self.write_projects(&projects)
+            .expect("Unable to save the sqlite database")
+    }
+
+    fn has_changed(&mut self) -> bool {
+        let Ok(data_version) = SqliteStorage::get_data_version(&self.connection) else {
+            return false;
+        };
+
+        if data_version == self.data_version {
+            return false;
+        }
+
+        self.data_version = data_version;
+        true
+    }
+}
+
+// One-shot conversions between the json file and the database
+impl SqliteStorage {
+    pub fn import_json() -> Result<usize, Box<dyn Error>> {
+        Json::check()?;
+
+        let projects = Json::read(&Json::get_current_path());
+        let count = projects.len();
+
+        // Keep the database that is going to be replaced, the days missing from the json are removed
+        SqliteStorage::backup(usize::MAX)?;
+
+        // A json file with a duplicated day (i.e. hand edited) is refused
+        SqliteStorage::open(&SqliteStorage::get_path())?.write_projects(&projects)?;
+
+        Ok(count)
+    }
+
+    pub fn export_json() -> Result<usize, Box<dyn Error>> {
+        Json::check()?;
+
+        let projects = SqliteStorage::open(&SqliteStorage::get_path())?.read_projects()?;
+        let count = projects.len();
+
+        // Keep the json data that is going to be replaced
+        Json::backup()?;
+        Json::write(&Json::get_current_path(), projects);
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_storage() -> SqliteStorage {
+        SqliteStorage::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn get_task(title: &str, status: u8) -> Task {
+        Task {
+            title: title.to_string(),
+            status: Status::new(status),
+            priority: 0,
+            target: 0.0,
+            progress: 0.0,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Reps,
+            track: None,
+        }
+    }
+
+    fn get_project(title: &str, tasks: Vec<Task>) -> Project {
+        Project {
+            title: title.to_string(),
+            tasks,
+            day_off: None,
+        }
+    }
+
+    fn get_task_ids(storage: &SqliteStorage, title: &str) -> Vec<i64> {
+        let mut stmt = storage
+            .connection
+            .prepare(
+                "SELECT tasks.id FROM tasks JOIN days ON days.id = tasks.day_id
+                WHERE days.title = ?1 ORDER BY position",
+            )
+            .unwrap();
+
+        stmt.query_map([title], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<i64>>>()
+            .unwrap()
+    }
+
+    fn get_history(storage: &SqliteStorage) -> Vec<(String, u8)> {
+        let mut stmt = storage
+            .connection
+            .prepare("SELECT task_title, status FROM status_history ORDER BY id")
+            .unwrap();
+
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn json_is_kept_by_the_import_and_the_export() {
+        let json = r#"[
+            {"title": "warm up", "tasks": [], "day_off": null},
+            {"title": "01.10.2026", "day_off": null, "tasks": [
+                {"title": "pushups", "status": 50, "priority": 1, "target": 36.0, "progress": 0.0,
+                "sets": [10, 12, 8], "weight": 20.5, "kind": "reps", "track": null},
+                {"title": "run", "status": 100, "priority": 0, "target": 5.0, "progress": 5.2,
+                "sets": [], "weight": 0.0, "kind": "distance", "track": {"start": "2026-10-01T07:00:00Z",
+                "distance_km": 5.2, "duration_s": 1800, "elevation_gain_m": 42.0}}
+            ]},
+            {"title": "02.10.2026", "tasks": [], "day_off": {"reason": "sick", "note": "flu"}}
+        ]"#;
+        let projects: Vec<Project> = serde_json::from_str(json).unwrap();
+
+        // import_json
+        let mut storage = get_storage();
+        storage.write_projects(&projects).unwrap();
+
+        // export_json
+        let exported = serde_json::to_value(storage.read()).unwrap();
+
+        assert_eq!(exported, serde_json::to_value(&projects).unwrap());
+    }
+
+    #[test]
+    fn only_the_changed_days_are_written() {
+        let mut storage = get_storage();
+        let mut projects = vec![
+            get_project("01.01.2026", vec![get_task("pushups", 0)]),
+            get_project("02.01.2026", vec![get_task("squats", 0)]),
+        ];
+
+        storage.write_projects(&projects).unwrap();
+        let first_ids = get_task_ids(&storage, "01.01.2026");
+
+        projects[1].tasks[0].status = Status::new(100);
+        storage.write_projects(&projects).unwrap();
+
+        // A rewritten day would get new ids
+        assert_eq!(get_task_ids(&storage, "01.01.2026"), first_ids);
+        assert_eq!(storage.read()[1].tasks[0].status, Status::new(100));
+
+        // A deleted day is removed
+        projects.remove(0);
+        storage.write_projects(&projects).unwrap();
+
+        let read = storage.read();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].tasks[0].status, Status::new(100));
+
+        // The duplicated days are refused and the database is unchanged
+        projects.push(get_project("02.01.2026", vec![]));
+        assert!(storage.write_projects(&projects).is_err());
+        assert_eq!(storage.read().len(), 1);
+    }
+
+    #[test]
+    fn status_changes_are_recorded_in_the_history() {
+        let mut storage = get_storage();
+        let mut projects = vec![get_project(
+            "01.01.2026",
+            vec![get_task("pushups", 0), get_task("squats", 0)],
+        )];
+
+        storage.write_projects(&projects).unwrap();
+
+        // Another change of the day doesn't add the unchanged statuses
+        projects[0].tasks[0].status = Status::new(50);
+        projects[0].tasks[1].weight = 10.0;
+        storage.write_projects(&projects).unwrap();
+
+        // Nothing changed
+        storage.write_projects(&projects).unwrap();
+
+        assert_eq!(
+            get_history(&storage),
+            [
+                ("pushups".to_string(), 0),
+                ("squats".to_string(), 0),
+                ("pushups".to_string(), 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn renamed_day_keeps_its_history() {
+        let mut storage = get_storage();
+        let mut projects = vec![
+            get_project("01.01.2026", vec![get_task("pushups", 0)]),
+            get_project("02.01.2026", vec![get_task("pushups", 0)]),
+        ];
+
+        storage.write_projects(&projects).unwrap();
+
+        projects[0].tasks[0].status = Status::new(50);
+        storage.write_projects(&projects).unwrap();
+        let ids = get_task_ids(&storage, "01.01.2026");
+
+        projects[0].title = "03.01.2026".to_string();
+        storage.write_projects(&projects).unwrap();
+
+        assert_eq!(get_task_ids(&storage, "03.01.2026"), ids);
+        assert_eq!(get_history(&storage).len(), 3);
+
+        // The date follows the title
+        let titles: Vec<String> = storage.read().into_iter().map(|p| p.title).collect();
+        assert_eq!(titles, ["02.01.2026", "03.01.2026"]);
+    }
+}
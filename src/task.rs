@@ -1,79 +1,355 @@
 use ratatui::{
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::ListItem,
 };
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
-use crate::{json::Json, util::Util, App};
+use crate::{util::Util, App};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Task {
     pub title: String,
-    pub status: String,
+    pub status: Status,
     pub priority: u8,
+    // Reps, seconds or kilometres depending on the kind
+    pub target: f32,
+    // Seconds or kilometres done, the reps are the sum of the sets
+    pub progress: f32,
+    pub sets: Vec<u32>,
+    pub weight: f32,
+    pub kind: TaskKind,
+    // Recorded session (i.e. imported from a gpx file)
+    pub track: Option<Track>,
+}
+
+// What is measured for the task
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    #[default]
+    Reps,
+    Duration,
+    Distance,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Track {
+    // RFC 3339 time of the first point, it identifies the track
+    pub start: String,
+    pub distance_km: f64,
+    pub duration_s: u64,
+    pub elevation_gain_m: f64,
+}
+
+// Completion of a task in percentage, the values over 100 are capped
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(from = "RawStatus", into = "u8")]
+pub struct Status(u8);
+
+// Status as found in the json file, it may be hand edited
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawStatus {
+    Number(f64),
+    Text(String),
+}
+
+pub const TASK_STATUS_ZERO: Status = Status(0);
+pub const TASK_STATUS_DONE: Status = Status(100);
+
+// Statuses that can be picked for a task
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusScale {
+    // 0, 25, 50, 75, 100
+    #[default]
+    Quarters,
+    // 0, 100
+    Binary,
+    // 0, 10, 20 ... 100
+    Tenths,
+    // Computed from the reps (or seconds, km) done against the target and kept as it is,
+    // i.e. 33. The quarters are picked by hand when there is no target
+    Reps,
 }
 
-pub const TASK_STATUS_ZERO: &str = "0";
-pub const TASK_STATUS_QUARTER: &str = "25";
-pub const TASK_STATUS_HALF: &str = "50";
-pub const TASK_STATUS_TREE_QUARTER: &str = "75";
-pub const TASK_STATUS_DONE: &str = "100";
-
-const TASK_STATUSES_SORT_ORDER: [&str; 5] = [
-    TASK_STATUS_ZERO,
-    TASK_STATUS_QUARTER,
-    TASK_STATUS_HALF,
-    TASK_STATUS_TREE_QUARTER,
-    TASK_STATUS_DONE,
-];
-pub const TASK_STATUSES: [&str; 5] = [
-    TASK_STATUS_ZERO,
-    TASK_STATUS_QUARTER,
-    TASK_STATUS_HALF,
-    TASK_STATUS_TREE_QUARTER,
-    TASK_STATUS_DONE,
-];
 pub const TASK_ITEMS_PE: [&str; 3] = ["pushups", "squats", "dumbbell"];
 
 // Ascending order: 1 highest priority; 2 medium; 3 lowest
 pub const TASK_PRIORITIES: [u8; 4] = [1, 2, 3, 0];
 
-impl Task {
-    fn get_status_color(status: &str) -> ratatui::prelude::Color {
-        match status {
-            TASK_STATUS_ZERO => Color::Gray,
-            TASK_STATUS_QUARTER => Color::White,
-            TASK_STATUS_HALF => Color::LightBlue,
-            TASK_STATUS_TREE_QUARTER => Color::LightMagenta,
-            TASK_STATUS_DONE => Color::LightYellow,
-            _ => Color::Gray,
+impl Status {
+    pub fn new(percentage: u8) -> Self {
+        Status(percentage.min(100))
+    }
+
+    pub fn from_progress(progress: f32, target: f32) -> Self {
+        if target <= 0.0 {
+            return TASK_STATUS_ZERO;
+        }
+
+        Status::new((progress * 100.0 / target).clamp(0.0, 100.0) as u8)
+    }
+
+    pub fn percentage(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_done(&self) -> bool {
+        *self == TASK_STATUS_DONE
+    }
+}
+
+impl From<RawStatus> for Status {
+    fn from(value: RawStatus) -> Self {
+        let percentage = match value {
+            RawStatus::Number(n) => n,
+            // Invalid values fallback to zero
+            RawStatus::Text(t) => t.trim().parse().unwrap_or(0.0),
+        };
+
+        Status::new(percentage.clamp(0.0, 100.0) as u8)
+    }
+}
+
+impl From<Status> for u8 {
+    fn from(status: Status) -> Self {
+        status.0
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TaskKind {
+    pub fn get_unit(&self) -> &'static str {
+        match self {
+            TaskKind::Reps => "",
+            TaskKind::Duration => "s",
+            TaskKind::Distance => "km",
+        }
+    }
+
+    pub fn next(&self) -> TaskKind {
+        match self {
+            TaskKind::Reps => TaskKind::Duration,
+            TaskKind::Duration => TaskKind::Distance,
+            TaskKind::Distance => TaskKind::Reps,
+        }
+    }
+
+    // At most 2 decimals, i.e. "45s", "5.21km"
+    pub fn get_value_repr(&self, value: f32) -> String {
+        format!("{}{}", (value * 100.0).round() / 100.0, self.get_unit())
+    }
+
+    // Title of the editor of the training numbers
+    pub fn get_input_title(&self) -> &'static str {
+        match self {
+            TaskKind::Reps => "Target reps / Reps per set / Kg",
+            TaskKind::Duration => "Target seconds / Seconds done",
+            TaskKind::Distance => "Target km / Km done",
         }
     }
+}
+
+impl StatusScale {
+    pub fn get_statuses(&self) -> Vec<Status> {
+        let step = match self {
+            StatusScale::Quarters | StatusScale::Reps => 25,
+            StatusScale::Binary => 100,
+            StatusScale::Tenths => 10,
+        };
+
+        (0..=100).step_by(step).map(Status::new).collect()
+    }
+
+    // A computed status rounded down to a status of the scale, so it can be picked again
+    // (and 99% isn't done with the binary scale). The reps scale keeps it as it is
+    pub fn snap(&self, status: Status) -> Status {
+        if *self == StatusScale::Reps {
+            return status;
+        }
+
+        self.get_statuses()
+            .into_iter()
+            .rev()
+            .find(|s| *s <= status)
+            .unwrap_or(TASK_STATUS_ZERO)
+    }
+}
 
-    pub fn load_statuses_items(items: &mut Vec<ListItem>) {
+impl Task {
+    pub fn load_statuses_items(app: &App, items: &mut Vec<ListItem>) {
         items.clear();
 
-        for status in TASK_STATUSES {
-            let span = Span::styled(status, Style::new().fg(Task::get_status_color(status)));
+        for status in app.config.status.scale.get_statuses() {
+            let span = Span::styled(
+                status.to_string(),
+                Style::new().fg(app.theme.get_status_color(status)),
+            );
 
             items.push(ListItem::from(span))
         }
     }
 
-    pub fn load_priority_items(items: &mut Vec<ListItem>) {
+    pub fn load_priority_items(app: &App, items: &mut Vec<ListItem>) {
         items.clear();
 
         for priority_value in TASK_PRIORITIES {
             let span = Span::styled(
                 Util::get_priority_indicator(priority_value),
-                Style::new().fg(Color::Red),
+                Style::new().fg(app.theme.priority),
             );
 
             items.push(ListItem::from(span))
         }
     }
 
+    // i.e. "in 0:28:14, +45m"
+    fn get_track_repr(track: &Track) -> String {
+        let mut repr = format!(
+            "in {}:{:02}:{:02}",
+            track.duration_s / 3600,
+            track.duration_s / 60 % 60,
+            track.duration_s % 60
+        );
+
+        if track.elevation_gain_m > 0.0 {
+            repr.push_str(&format!(", +{:.0}m", track.elevation_gain_m));
+        }
+
+        repr
+    }
+
+    // i.e. "45s / 60s", "5.21km in 0:28:14, +45m"
+    fn get_progress_repr(task: &Task) -> Option<String> {
+        if task.target == 0.0 && task.progress == 0.0 && task.track.is_none() {
+            return None;
+        }
+
+        let mut repr = task.kind.get_value_repr(task.progress);
+
+        if task.target > 0.0 {
+            repr.push_str(&format!(" / {}", task.kind.get_value_repr(task.target)));
+        }
+
+        if let Some(track) = &task.track {
+            repr.push_str(&format!(" {}", Task::get_track_repr(track)));
+        }
+
+        Some(repr)
+    }
+
+    // Human readable summary of the training numbers, i.e. "10+12+8 = 30/36 @ 20kg"
+    pub fn get_stats_repr(task: &Task) -> Option<String> {
+        if task.kind != TaskKind::Reps {
+            return Task::get_progress_repr(task);
+        }
+
+        if task.target == 0.0 && task.sets.is_empty() && task.weight == 0.0 {
+            return None;
+        }
+
+        let done_reps: u32 = task.sets.iter().sum();
+        let sets: Vec<String> = task.sets.iter().map(|s| s.to_string()).collect();
+
+        let mut repr = if sets.is_empty() {
+            format!("{}/{}", done_reps, task.target)
+        } else {
+            format!("{} = {}/{}", sets.join("+"), done_reps, task.target)
+        };
+
+        if task.weight > 0.0 {
+            repr.push_str(&format!(" @ {}kg", task.weight));
+        }
+
+        Some(repr)
+    }
+
+    // Value used to prefill the sets editor, i.e. "36 / 10 12 8 / 20" or "60s / 45s"
+    pub fn get_sets_input_value(task: &Task) -> String {
+        if task.kind != TaskKind::Reps {
+            return format!(
+                "{} / {}",
+                task.kind.get_value_repr(task.target),
+                task.kind.get_value_repr(task.progress)
+            );
+        }
+
+        let sets: Vec<String> = task.sets.iter().map(|s| s.to_string()).collect();
+
+        format!("{} / {} / {}", task.target, sets.join(" "), task.weight)
+    }
+
+    // Parse a number of the editor, the unit is optional (i.e. "45s" or "45")
+    fn parse_input_number(value: &str, unit: &str) -> Option<f32> {
+        let value = value.trim_end_matches(unit).trim();
+
+        if value.is_empty() {
+            return Some(0.0);
+        }
+
+        value.parse().ok().filter(|v| Task::is_valid_number(*v))
+    }
+
+    // Targets, progress and weights can't be negative, NaN or infinite
+    pub fn is_valid_number(value: f32) -> bool {
+        value >= 0.0 && value.is_finite()
+    }
+
+    // Reps, seconds or kilometres done, the reps are the sum of the sets
+    fn get_done(task: &Task) -> f32 {
+        match task.kind {
+            TaskKind::Reps => task.sets.iter().sum::<u32>() as f32,
+            _ => task.progress,
+        }
+    }
+
+    // Parse the progress editor value: "<target> / <done>"
+    fn parse_progress_input_value(value: &str, kind: TaskKind) -> Option<(f32, f32)> {
+        let parts: Vec<&str> = value.split('/').map(|p| p.trim()).collect();
+
+        if parts.len() != 2 {
+            return None;
+        }
+
+        Some((
+            Task::parse_input_number(parts[0], kind.get_unit())?,
+            Task::parse_input_number(parts[1], kind.get_unit())?,
+        ))
+    }
+
+    // Parse the sets editor value: "<target reps> / <reps per set...> / <kg>"
+    fn parse_sets_input_value(value: &str) -> Option<(u32, Vec<u32>, f32)> {
+        let parts: Vec<&str> = value.split('/').map(|p| p.trim()).collect();
+
+        if parts.len() != 3 {
+            return None;
+        }
+
+        let target_reps = if parts[0].is_empty() {
+            0
+        } else {
+            parts[0].parse().ok()?
+        };
+
+        let sets = parts[1]
+            .split_whitespace()
+            .map(|s| s.parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+
+        // Like the other numbers of the editor, a weight can't be negative or NaN
+        let weight = Task::parse_input_number(parts[2], "kg")?;
+
+        Some((target_reps, sets, weight))
+    }
+
     pub fn load_items(app: &mut App, items: &mut Vec<ListItem>) {
         let tasks = &mut app.projects[app.selected_project_index.selected().unwrap()].tasks;
 
@@ -82,18 +358,20 @@ impl Task {
             .get(app.selected_task_index.selected().unwrap_or(0))
             .unwrap_or(&Task {
                 title: "".to_string(),
-                status: "".to_string(),
+                status: TASK_STATUS_ZERO,
                 priority: 0,
+                target: 0.0,
+                progress: 0.0,
+                sets: vec![],
+                weight: 0.0,
+                kind: TaskKind::Reps,
+                track: None,
             })
             .clone()
             .title;
 
         // Sort by status
-        tasks.sort_by_key(|t| {
-            TASK_STATUSES_SORT_ORDER
-                .into_iter()
-                .position(|o| o == t.status)
-        });
+        tasks.sort_by_key(|t| t.status);
 
         // Sort by priority
         tasks.sort_by_key(|t| TASK_PRIORITIES.into_iter().position(|o| o == t.priority));
@@ -106,7 +384,7 @@ impl Task {
         items.clear();
 
         for task in tasks.iter() {
-            let modifier = if task.status == TASK_STATUS_DONE {
+            let modifier = if task.status.is_done() {
                 Modifier::CROSSED_OUT
             } else {
                 Modifier::empty()
@@ -116,16 +394,23 @@ impl Task {
                 Span::styled(
                     format!("[{}] ", task.status),
                     Style::default()
-                        .fg(Task::get_status_color(&task.status))
+                        .fg(app.theme.get_status_color(task.status))
                         .add_modifier(modifier),
                 ),
                 Span::styled(task.title.clone(), Style::default().add_modifier(modifier)),
             ];
 
+            if let Some(stats) = Task::get_stats_repr(task) {
+                repr.push(Span::styled(
+                    format!("  {}", stats),
+                    Style::new().fg(app.theme.muted),
+                ));
+            }
+
             if task.priority != 0 {
                 let priority_repr = vec![Span::styled(
                     format!("[{}] ", Util::get_priority_indicator(task.priority)),
-                    Style::new().fg(Color::Red),
+                    Style::new().fg(app.theme.priority),
                 )];
                 repr = [priority_repr, repr].concat()
             }
@@ -139,7 +424,7 @@ impl Task {
     }
 
     pub fn reload(app: &mut App, items: &mut Vec<ListItem>) {
-        app.projects = Json::read();
+        app.projects = app.storage.read();
         Task::load_items(app, items)
     }
 
@@ -159,8 +444,14 @@ impl Task {
 
         let new_task = Task {
             title: value.to_string(),
-            status: TASK_STATUS_ZERO.to_string(),
+            status: TASK_STATUS_ZERO,
             priority: 0,
+            target: 0.0,
+            progress: 0.0,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Reps,
+            track: None,
         };
 
         let mut internal_projects = app.projects.clone();
@@ -168,7 +459,7 @@ impl Task {
             .tasks
             .push(new_task);
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Task::reload(app, items)
     }
 
@@ -179,18 +470,18 @@ impl Task {
             [app.selected_task_index.selected().unwrap()]
         .title = value.to_string();
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Task::reload(app, items)
     }
 
-    pub fn change_status(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
+    pub fn change_status(app: &mut App, items: &mut Vec<ListItem>, value: Status) {
         let mut internal_projects = app.projects.clone();
 
         internal_projects[app.selected_project_index.selected().unwrap()].tasks
             [app.selected_task_index.selected().unwrap()]
-        .status = value.to_string();
+        .status = value;
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Task::reload(app, items)
     }
 
@@ -201,7 +492,54 @@ impl Task {
             [app.selected_task_index.selected().unwrap()]
         .priority = value;
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
+        Task::reload(app, items)
+    }
+
+    pub fn change_sets(app: &mut App, items: &mut Vec<ListItem>, value: &str) {
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+
+        if task.kind == TaskKind::Reps {
+            // Ignore the value if it doesn't follow the editor format
+            let Some((target_reps, sets, weight)) = Task::parse_sets_input_value(value) else {
+                return;
+            };
+
+            task.target = target_reps as f32;
+            task.sets = sets;
+            task.weight = weight;
+        } else {
+            let Some((target, progress)) = Task::parse_progress_input_value(value, task.kind)
+            else {
+                return;
+            };
+
+            task.target = target;
+            task.progress = progress;
+        }
+
+        // With a target the status is computed for every kind, without one it's picked by hand
+        if task.target > 0.0 {
+            let status = Status::from_progress(Task::get_done(task), task.target);
+            task.status = app.config.status.scale.snap(status);
+        }
+
+        app.storage.write(internal_projects);
+        Task::reload(app, items)
+    }
+
+    pub fn change_kind(app: &mut App, items: &mut Vec<ListItem>) {
+        let mut internal_projects = app.projects.clone();
+
+        let task = &mut internal_projects[app.selected_project_index.selected().unwrap()].tasks
+            [app.selected_task_index.selected().unwrap()];
+
+        task.kind = task.kind.next();
+
+        app.storage.write(internal_projects);
         Task::reload(app, items)
     }
 
@@ -212,7 +550,161 @@ impl Task {
             .tasks
             .remove(app.selected_task_index.selected().unwrap());
 
-        Json::write(internal_projects);
+        app.storage.write(internal_projects);
         Task::reload(app, items)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, project::Project, storage::memory::MemoryStorage};
+
+    fn get_task(kind: TaskKind, target: f32, progress: f32) -> Task {
+        Task {
+            title: "plank".to_string(),
+            status: TASK_STATUS_ZERO,
+            priority: 0,
+            target,
+            progress,
+            sets: vec![],
+            weight: 0.0,
+            kind,
+            track: None,
+        }
+    }
+
+    #[test]
+    fn progress_is_shown_against_the_target_with_the_unit() {
+        let plank = get_task(TaskKind::Duration, 60.0, 45.0);
+        let run = get_task(TaskKind::Distance, 5.0, 4.216);
+
+        assert_eq!(Task::get_stats_repr(&plank), Some("45s / 60s".to_string()));
+        assert_eq!(Task::get_stats_repr(&run), Some("4.22km / 5km".to_string()));
+        assert_eq!(Task::get_sets_input_value(&plank), "60s / 45s");
+        assert_eq!(
+            Task::parse_progress_input_value("60s / 45", TaskKind::Duration),
+            Some((60.0, 45.0))
+        );
+        assert_eq!(Status::from_progress(45.0, 60.0), Status::new(75));
+        assert_eq!(Status::from_progress(90.0, 60.0), TASK_STATUS_DONE);
+    }
+
+    #[test]
+    fn sets_editor_rejects_malformed_values() {
+        assert_eq!(
+            Task::parse_sets_input_value("36 / 10 12 8 / 20kg"),
+            Some((36, vec![10, 12, 8], 20.0))
+        );
+        assert_eq!(
+            Task::parse_sets_input_value(" / / "),
+            Some((0, vec![], 0.0))
+        );
+
+        // Target
+        assert_eq!(Task::parse_sets_input_value("-3 / 10 / 20"), None);
+        assert_eq!(Task::parse_sets_input_value("ten / 10 / 20"), None);
+        // Sets
+        assert_eq!(Task::parse_sets_input_value("36 / 10 x 8 / 20"), None);
+        assert_eq!(Task::parse_sets_input_value("36 / 10 -2 / 20"), None);
+        // Weight
+        assert_eq!(Task::parse_sets_input_value("36 / 10 / NaN"), None);
+        assert_eq!(Task::parse_sets_input_value("36 / 10 / inf"), None);
+        assert_eq!(Task::parse_sets_input_value("36 / 10 / -5kg"), None);
+        // Missing part
+        assert_eq!(Task::parse_sets_input_value("36 / 10"), None);
+    }
+
+    #[test]
+    fn status_is_read_from_numbers_and_legacy_strings() {
+        let get_status = |json: &str| serde_json::from_str::<Status>(json).unwrap();
+
+        // Legacy strings (before the status became a number)
+        assert_eq!(get_status(r#""25""#), Status::new(25));
+        assert_eq!(get_status(r#"" 75 ""#), Status::new(75));
+        assert_eq!(get_status(r#""100""#), TASK_STATUS_DONE);
+
+        // Out of range numbers are capped
+        assert_eq!(get_status("50"), Status::new(50));
+        assert_eq!(get_status("250"), TASK_STATUS_DONE);
+        assert_eq!(get_status("-10"), TASK_STATUS_ZERO);
+        assert_eq!(get_status(r#""250""#), TASK_STATUS_DONE);
+
+        // Unknown strings fallback to zero
+        assert_eq!(get_status(r#""done""#), TASK_STATUS_ZERO);
+        assert_eq!(get_status(r#""""#), TASK_STATUS_ZERO);
+
+        assert_eq!(serde_json::to_string(&Status::new(75)).unwrap(), "75");
+    }
+
+    #[test]
+    fn each_scale_has_its_statuses() {
+        let get_percentages = |scale: StatusScale| -> Vec<u8> {
+            scale
+                .get_statuses()
+                .iter()
+                .map(|s| s.percentage())
+                .collect()
+        };
+
+        assert_eq!(get_percentages(StatusScale::Quarters), [0, 25, 50, 75, 100]);
+        assert_eq!(get_percentages(StatusScale::Binary), [0, 100]);
+        assert_eq!(
+            get_percentages(StatusScale::Tenths),
+            [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100]
+        );
+        // Without a target the reps are picked by quarters
+        assert_eq!(get_percentages(StatusScale::Reps), [0, 25, 50, 75, 100]);
+
+        // Only the reps scale keeps the computed status as it is
+        assert_eq!(StatusScale::Reps.snap(Status::new(33)), Status::new(33));
+        assert_eq!(StatusScale::Quarters.snap(Status::new(33)), Status::new(25));
+        assert_eq!(StatusScale::Binary.snap(Status::new(99)), TASK_STATUS_ZERO);
+        assert_eq!(StatusScale::Tenths.snap(TASK_STATUS_DONE), TASK_STATUS_DONE);
+    }
+
+    #[test]
+    fn status_is_computed_from_the_target_of_every_kind() {
+        let project = Project {
+            title: "01.01.2026".to_string(),
+            tasks: vec![get_task(TaskKind::Reps, 0.0, 0.0)],
+            day_off: None,
+        };
+
+        // The default scale is the quarters one
+        let mut app = App::setup(
+            Config::get_default(),
+            Box::new(MemoryStorage::new(vec![project.clone()])),
+        );
+        let mut items: Vec<ListItem> = vec![];
+
+        // 30 reps of 40 are 75%
+        Task::change_sets(&mut app, &mut items, "40 / 10 10 10 / 20");
+        assert_eq!(app.projects[0].tasks[0].status, Status::new(75));
+
+        // Without a target the status is left as it was picked
+        Task::change_status(&mut app, &mut items, Status::new(50));
+        Task::change_sets(&mut app, &mut items, "0 / 10 10 10 10 / 20");
+        assert_eq!(app.projects[0].tasks[0].status, Status::new(50));
+
+        Task::change_kind(&mut app, &mut items);
+        Task::change_sets(&mut app, &mut items, "60 / 45");
+        assert_eq!(app.projects[0].tasks[0].status, Status::new(75));
+
+        // 10 reps of 30 are 33%, rounded down to a status of the scale
+        for (scale, status) in [
+            (StatusScale::Quarters, 25),
+            (StatusScale::Binary, 0),
+            (StatusScale::Tenths, 30),
+            (StatusScale::Reps, 33),
+        ] {
+            let mut config = Config::get_default();
+            config.status.scale = scale;
+
+            let mut app = App::setup(config, Box::new(MemoryStorage::new(vec![project.clone()])));
+
+            Task::change_sets(&mut app, &mut items, "30 / 10 / 20");
+            assert_eq!(app.projects[0].tasks[0].status, Status::new(status));
+        }
+    }
+}
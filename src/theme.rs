@@ -0,0 +1,226 @@
+use std::env;
+
+use ratatui::style::Color;
+
+use crate::{
+    config::{ThemeConfig, ThemeName},
+    task::Status,
+};
+
+// Shades of the grid activity levels when the colours are disabled
+const NO_COLOR_BLOCKS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+const NO_COLOR_DAY_OFF_BLOCK: &str = "○";
+
+// Colours of the TUI, cf. the [theme] section of config.toml
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    // Status 0, under 50, under 75, under 100 and done
+    pub status: [Color; 5],
+    // Done tasks of a day: none, from a quarter, from a half, all of them and the others
+    pub done_tasks: [Color; 5],
+    pub priority: Color,
+    // Secondary text (i.e. the sets, the labels of the grid activity)
+    pub muted: Color,
+    // Grid activity, from no activity to every exercise done
+    pub heatmap: [Color; 5],
+    pub day_off: Color,
+    // The grid activity levels are drawn with shades instead of colours
+    pub no_color: bool,
+}
+
+impl Theme {
+    /// Constructor for Theme, the colours are disabled by the NO_COLOR environment variable
+    /// cf. https://no-color.org
+    pub fn new(config: &ThemeConfig) -> Self {
+        let no_color = env::var("NO_COLOR").is_ok_and(|v| !v.is_empty());
+
+        Theme::from_config(config, no_color)
+    }
+
+    fn from_config(config: &ThemeConfig, no_color: bool) -> Self {
+        if no_color {
+            return Theme {
+                status: [Color::Reset; 5],
+                done_tasks: [Color::Reset; 5],
+                priority: Color::Reset,
+                muted: Color::Reset,
+                heatmap: [Color::Reset; 5],
+                day_off: Color::Reset,
+                no_color,
+            };
+        }
+
+        let mut theme = match config.name {
+            ThemeName::Dark => Theme::get_dark(),
+            ThemeName::Light => Theme::get_light(),
+            ThemeName::HighContrast => Theme::get_high_contrast(),
+        };
+
+        // The colours set in the config replace the ones of the theme
+        theme.status = config.status.unwrap_or(theme.status);
+        theme.done_tasks = config.done_tasks.unwrap_or(theme.done_tasks);
+        theme.priority = config.priority.unwrap_or(theme.priority);
+        theme.muted = config.muted.unwrap_or(theme.muted);
+        theme.heatmap = config.heatmap.unwrap_or(theme.heatmap);
+        theme.day_off = config.day_off.unwrap_or(theme.day_off);
+
+        theme
+    }
+
+    fn get_dark() -> Self {
+        Theme {
+            status: [
+                Color::Gray,
+                Color::White,
+                Color::LightBlue,
+                Color::LightMagenta,
+                Color::LightYellow,
+            ],
+            done_tasks: [
+                Color::DarkGray,
+                Color::LightMagenta,
+                Color::LightYellow,
+                Color::LightGreen,
+                Color::White,
+            ],
+            priority: Color::Red,
+            muted: Color::DarkGray,
+            // github colours
+            heatmap: [
+                Color::Rgb(22, 27, 34),
+                Color::Rgb(14, 68, 41),
+                Color::Rgb(0, 109, 50),
+                Color::Rgb(38, 166, 65),
+                Color::Rgb(57, 211, 83),
+            ],
+            day_off: Color::Rgb(56, 139, 253),
+            no_color: false,
+        }
+    }
+
+    fn get_light() -> Self {
+        Theme {
+            status: [
+                Color::DarkGray,
+                Color::Black,
+                Color::Blue,
+                Color::Magenta,
+                Color::Rgb(154, 103, 0),
+            ],
+            done_tasks: [
+                Color::DarkGray,
+                Color::Magenta,
+                Color::Rgb(154, 103, 0),
+                Color::Green,
+                Color::Black,
+            ],
+            priority: Color::Red,
+            muted: Color::DarkGray,
+            heatmap: [
+                Color::Rgb(235, 237, 240),
+                Color::Rgb(155, 233, 168),
+                Color::Rgb(64, 196, 99),
+                Color::Rgb(48, 161, 78),
+                Color::Rgb(33, 110, 57),
+            ],
+            day_off: Color::Rgb(9, 105, 218),
+            no_color: false,
+        }
+    }
+
+    // Only the 16 basic colours, in their bright variant
+    fn get_high_contrast() -> Self {
+        Theme {
+            status: [
+                Color::White,
+                Color::LightCyan,
+                Color::LightBlue,
+                Color::LightMagenta,
+                Color::LightYellow,
+            ],
+            done_tasks: [
+                Color::White,
+                Color::LightMagenta,
+                Color::LightYellow,
+                Color::LightGreen,
+                Color::White,
+            ],
+            priority: Color::LightRed,
+            muted: Color::White,
+            heatmap: [
+                Color::DarkGray,
+                Color::Blue,
+                Color::LightCyan,
+                Color::LightGreen,
+                Color::White,
+            ],
+            day_off: Color::LightMagenta,
+            no_color: false,
+        }
+    }
+
+    pub fn get_status_color(&self, status: Status) -> Color {
+        match status.percentage() {
+            0 => self.status[0],
+            p if p < 50 => self.status[1],
+            p if p < 75 => self.status[2],
+            p if p < 100 => self.status[3],
+            _ => self.status[4],
+        }
+    }
+
+    pub fn get_done_tasks_color(&self, percentage: usize) -> Color {
+        match percentage {
+            0 => self.done_tasks[0],
+            p if (25..=49).contains(&p) => self.done_tasks[1],
+            p if (50..99).contains(&p) => self.done_tasks[2],
+            100 => self.done_tasks[3],
+            _ => self.done_tasks[4],
+        }
+    }
+
+    // Block of a grid activity level (0 no activity, 4 every exercise done)
+    pub fn get_heatmap_block(&self, level: usize, block: &'static str) -> &'static str {
+        if self.no_color {
+            NO_COLOR_BLOCKS[level.min(4)]
+        } else {
+            block
+        }
+    }
+
+    pub fn get_day_off_block(&self, block: &'static str) -> &'static str {
+        if self.no_color {
+            NO_COLOR_DAY_OFF_BLOCK
+        } else {
+            block
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_colours_replace_the_ones_of_the_theme() {
+        let config: ThemeConfig = toml::from_str(
+            r##"
+            name = "high-contrast"
+            priority = "magenta"
+            day_off = "#ff8800"
+            "##,
+        )
+        .unwrap();
+
+        let theme = Theme::from_config(&config, false);
+
+        assert_eq!(theme.priority, Color::Magenta);
+        assert_eq!(theme.day_off, Color::Rgb(255, 136, 0));
+        assert_eq!(theme.heatmap, Theme::get_high_contrast().heatmap);
+
+        let theme = Theme::from_config(&config, true);
+
+        assert_eq!(theme.priority, Color::Reset);
+        assert_eq!(theme.get_heatmap_block(4, "■"), "█");
+    }
+}
@@ -1,120 +1,358 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use ratatui::style::Color;
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::layout::Rect;
 
 use crate::{
-    project::Project,
-    task::{
-        TASK_STATUS_DONE, TASK_STATUS_HALF, TASK_STATUS_QUARTER, TASK_STATUS_TREE_QUARTER,
-        TASK_STATUS_ZERO,
-    },
+    project::{Project, PROJECT_DATE_FORMAT},
+    stats::Stats,
+    task::Task,
 };
 
-pub const COLORS: [Color; 5] = [
-    ratatui::style::Color::Rgb(57, 211, 83), // Lightest Green
-    ratatui::style::Color::Rgb(38, 166, 65), // Light Green
-    ratatui::style::Color::Rgb(0, 109, 50),  // Green
-    ratatui::style::Color::Rgb(14, 68, 41),  // Dark Green
-    ratatui::style::Color::Rgb(22, 27, 34),  // Darkest Green
-];
+// Title, month labels, a row for each weekday, the legend and the tooltip of the selected day
+pub const GRID_HEIGHT: u16 = 11;
+// Weekday labels (i.e. "Mon ")
+pub const GRID_LABEL_WIDTH: u16 = 4;
+// A block and a space
+pub const GRID_CELL_WIDTH: u16 = 2;
+pub const GRID_BLOCK: &str = "■";
 
-pub struct GridActivity {
-    pub start_offset: u16,
-    pub row_spacing: u16,
-    pub col_spacing: u16,
-    pub block_conf: GridBlockConf,
-    pub blocks: Vec<Vec<GridBlock>>,
+const MAX_WEEKS: u16 = 52;
+
+// What a day of the grid activity shows, the colours come from the theme
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GridCell {
+    // 0 no activity, 4 every exercise done
+    Activity(usize),
+    // Rest, sick or travel
+    DayOff,
 }
 
-pub struct GridBlock {
-    pub color: Color,
+// Exercises coloured by the grid activity
+#[derive(Default, Clone, PartialEq, Debug)]
+pub enum GridFilter {
+    #[default]
+    All,
+    Exercise(String),
+    // Name of the tag and the titles of its exercises
+    Tag(String, BTreeSet<String>),
+}
+
+impl GridFilter {
+    // Every exercise, then the tags and the exercises of the history sorted by name
+    pub fn get_all(projects: &[Project], tags: BTreeMap<String, BTreeSet<String>>) -> Vec<Self> {
+        let exercises: BTreeSet<&str> = projects
+            .iter()
+            .flat_map(|p| p.tasks.iter().map(|t| t.title.as_str()))
+            .collect();
+
+        let mut filters = vec![GridFilter::All];
+        filters.extend(
+            tags.into_iter()
+                .map(|(tag, titles)| GridFilter::Tag(tag, titles)),
+        );
+        filters.extend(
+            exercises
+                .into_iter()
+                .map(|t| GridFilter::Exercise(t.to_string())),
+        );
+
+        filters
+    }
+
+    // The filter after this one, back to all after the last one
+    pub fn next(&self, projects: &[Project], tags: BTreeMap<String, BTreeSet<String>>) -> Self {
+        let filters = GridFilter::get_all(projects, tags);
+
+        let index = filters
+            .iter()
+            .position(|f| f == self)
+            .map_or(0, |i| (i + 1) % filters.len());
+
+        filters[index].clone()
+    }
+
+    pub fn matches(&self, task: &Task) -> bool {
+        match self {
+            GridFilter::All => true,
+            GridFilter::Exercise(title) => task.title.eq_ignore_ascii_case(title),
+            GridFilter::Tag(_, titles) => {
+                titles.iter().any(|t| t.eq_ignore_ascii_case(&task.title))
+            }
+        }
+    }
+
+    // i.e. "all exercises", "squats", "#legs"
+    pub fn get_title(&self) -> String {
+        match self {
+            GridFilter::All => "all exercises".to_string(),
+            GridFilter::Exercise(title) => title.clone(),
+            GridFilter::Tag(tag, _) => format!("#{tag}"),
+        }
+    }
 }
 
-pub struct GridBlockConf {
-    pub width: u16,
-    pub height: u16,
-    pub view: String,
+// Calendar like the github one: a column for each week (monday first) and a row for
+// each weekday, the last column is the week of `today`
+pub struct GridActivity {
+    // Monday of the first column
+    pub first_date: NaiveDate,
+    pub weeks: u16,
+    pub today: NaiveDate,
 }
 
-// github grid activity have 6x52 size
 impl GridActivity {
-    /// Constructor for GridGrid
-    pub fn new(
-        start_offset: u16,
-        row_spacing: u16,
-        col_spacing: u16,
-        grid_block_conf: GridBlockConf,
-        blocks: Vec<Vec<GridBlock>>,
-    ) -> Self {
+    /// Constructor for GridActivity, with as many weeks (up to 52) as fit in `width`
+    pub fn new(width: u16, today: NaiveDate) -> Self {
+        let weeks = (width.saturating_sub(GRID_LABEL_WIDTH) / GRID_CELL_WIDTH).clamp(1, MAX_WEEKS);
+        let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
         Self {
-            start_offset,
-            row_spacing,
-            col_spacing,
-            block_conf: grid_block_conf,
-            blocks,
+            first_date: monday - Duration::weeks(weeks as i64 - 1),
+            weeks,
+            today,
+        }
+    }
+
+    // Date of a cell, none for the days after today
+    pub fn get_date(&self, week: u16, weekday: u16) -> Option<NaiveDate> {
+        let date = self.first_date + Duration::days(week as i64 * 7 + weekday as i64);
+
+        (date <= self.today).then_some(date)
+    }
+
+    // Move the date by `days`, staying inside the calendar
+    pub fn move_date(&self, date: NaiveDate, days: i64) -> NaiveDate {
+        (date + Duration::days(days)).clamp(self.first_date, self.today)
+    }
+
+    // The calendar is centered in the area
+    pub fn get_area(&self, area: Rect) -> Rect {
+        let width = GRID_LABEL_WIDTH + self.weeks * GRID_CELL_WIDTH;
+
+        Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            width: width.min(area.width),
+            height: area.height.min(GRID_HEIGHT),
+            ..area
+        }
+    }
+
+    // Date of the cell at a position of the terminal (i.e. a mouse click)
+    pub fn get_date_at(&self, area: Rect, column: u16, row: u16) -> Option<NaiveDate> {
+        let area = self.get_area(area);
+
+        // The first rows have the title and the month labels
+        let weekday = row.checked_sub(area.y + 2)?;
+        let week = column.checked_sub(area.x + GRID_LABEL_WIDTH)? / GRID_CELL_WIDTH;
+
+        if weekday >= 7 || week >= self.weeks {
+            return None;
+        }
+
+        self.get_date(week, weekday)
+    }
+
+    // i.e. "Sun 18.10.2026: 1/2 done (75%), pushups, squats", with the exercises of the filter
+    pub fn get_tooltip(projects: &[Project], date: NaiveDate, filter: &GridFilter) -> String {
+        let title = date.format(PROJECT_DATE_FORMAT).to_string();
+        let label = date.format("%a %d.%m.%Y");
+
+        let Some(project) = projects.iter().find(|p| p.title == title) else {
+            return format!("{label}: no day");
+        };
+
+        if let Some(day_off) = &project.day_off {
+            return format!("{label}: day off, {}", day_off.get_repr());
+        }
+
+        let Some(completion) = Stats::get_filtered_completion(project, |t| filter.matches(t))
+        else {
+            return match filter {
+                GridFilter::All => format!("{label}: rest day"),
+                _ => format!("{label}: no {}", filter.get_title()),
+            };
+        };
+
+        let tasks: Vec<&Task> = project.tasks.iter().filter(|t| filter.matches(t)).collect();
+        let done = tasks.iter().filter(|t| t.status.is_done()).count();
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+
+        format!(
+            "{label}: {done}/{} done ({completion:.0}%), {}",
+            tasks.len(),
+            titles.join(", ")
+        )
+    }
+
+    // Label of the month above the week of its first day (i.e. "Oct"), the first week
+    // gets the month it shows unless the label would overlap the next one
+    pub fn get_month_labels(&self) -> Vec<(u16, String)> {
+        let mut labels: Vec<(u16, String)> = vec![];
+
+        for week in 0..self.weeks {
+            let monday = self.first_date + Duration::weeks(week as i64);
+            let sunday = monday + Duration::days(6);
+
+            let label = if sunday.day() <= 7 {
+                sunday.format("%b").to_string()
+            } else if week == 0 {
+                monday.format("%b").to_string()
+            } else {
+                continue;
+            };
+
+            // The labels are 3 characters, wider than a week
+            if labels.last().is_some_and(|(last, _)| week < last + 2) {
+                labels.pop();
+            }
+
+            labels.push((week, label));
+        }
+
+        labels
+    }
+
+    // Cell of each day with a project, the first project is kept for a duplicated title
+    pub fn get_cells(projects: &[Project], filter: &GridFilter) -> HashMap<NaiveDate, GridCell> {
+        let mut cells = HashMap::new();
+
+        for project in projects {
+            if let Ok(date) = NaiveDate::parse_from_str(&project.title, PROJECT_DATE_FORMAT) {
+                cells
+                    .entry(date)
+                    .or_insert_with(|| GridActivity::get_project_cell(project, filter));
+            }
         }
+
+        cells
     }
 
-    pub fn convert_project_to_activityf32(project: &Project) -> f32 {
-        let mut statuses = vec![];
+    pub fn convert_project_to_activityf32(project: &Project, filter: &GridFilter) -> f32 {
+        // arithmetic mean of the statuses, shared with the streaks
+        Stats::get_filtered_completion(project, |t| filter.matches(t)).unwrap_or(0.0)
+    }
 
-        for task in project.tasks.iter() {
-            let status: f32 = task.status.parse().unwrap();
-            statuses.push(status);
+    pub fn get_project_cell(project: &Project, filter: &GridFilter) -> GridCell {
+        if project.day_off.is_some() {
+            return GridCell::DayOff;
         }
 
-        // calc arithmetic mean
-        let activity: f32 = statuses.iter().sum::<f32>() / statuses.len() as f32;
-        activity
+        let activity_i32 = Self::convert_project_to_activityf32(project, filter) as i32;
+        GridCell::Activity(Self::convert_activityi32_to_level(&activity_i32))
     }
 
-    pub fn convert_activityi32_to_color(activity: &i32) -> Color {
-        // make a map where key is status and activity value
-        // Parse the status constants into f32
-        let zero: i32 = TASK_STATUS_ZERO.parse().unwrap();
-        let quarter: i32 = TASK_STATUS_QUARTER.parse().unwrap();
-        let half: i32 = TASK_STATUS_HALF.parse().unwrap();
-        let tree_quarter: i32 = TASK_STATUS_TREE_QUARTER.parse().unwrap();
-        let done: i32 = TASK_STATUS_DONE.parse().unwrap();
+    pub fn convert_activityi32_to_level(activity: &i32) -> usize {
+        // make a map where key is status and activity level
+        let zero = 0;
+        let quarter = 25;
+        let half = 50;
+        let tree_quarter = 75;
+        let done = 100;
 
         // Create the HashMap with numeric ranges
         let mut range_color_map = HashMap::new();
-        range_color_map.insert(zero..quarter, COLORS[4]);
-        range_color_map.insert(quarter..half, COLORS[3]);
-        range_color_map.insert(half..tree_quarter, COLORS[2]);
-        range_color_map.insert(tree_quarter..done, COLORS[1]);
-        range_color_map.insert(done..done + 1, COLORS[0]);
+        range_color_map.insert(zero..quarter, 0);
+        range_color_map.insert(quarter..half, 1);
+        range_color_map.insert(half..tree_quarter, 2);
+        range_color_map.insert(tree_quarter..done, 3);
+        range_color_map.insert(done..done + 1, 4);
 
-        Self::get_color_for_activity(&range_color_map, *activity)
+        Self::get_level_for_activity(&range_color_map, *activity)
     }
 
-    fn get_color_for_activity(
-        range_color_map: &HashMap<std::ops::Range<i32>, Color>,
+    fn get_level_for_activity(
+        range_color_map: &HashMap<std::ops::Range<i32>, usize>,
         activity: i32,
-    ) -> Color {
-        for (range, color) in range_color_map {
+    ) -> usize {
+        for (range, level) in range_color_map {
             if range.contains(&activity) {
-                return *color;
+                return *level;
             }
         }
-        COLORS[4] // Default color if no range matches
+        0 // Default level if no range matches
     }
 }
 
-impl GridBlock {
-    /// Constructor for GridBlock
-    pub fn new(color: Color) -> Self {
-        Self { color }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Status, TaskKind};
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
     }
-}
 
-impl GridBlockConf {
-    /// Constructor for GridBlock
-    pub fn new(width: u16, height: u16, view: String) -> Self {
-        Self {
-            width,
-            height,
-            view,
-        }
+    #[test]
+    fn columns_are_weeks_starting_on_monday() {
+        // Sunday
+        let today = today();
+        let grid = GridActivity::new(GRID_LABEL_WIDTH + 4 * GRID_CELL_WIDTH, today);
+
+        assert_eq!(grid.weeks, 4);
+        assert_eq!(
+            grid.first_date,
+            NaiveDate::from_ymd_opt(2026, 9, 21).unwrap()
+        );
+        assert_eq!(grid.get_date(3, 6), Some(today));
+        assert_eq!(grid.get_month_labels(), [(1, "Oct".to_string())]);
+
+        // The first rows have the title and the months, the first columns the weekdays
+        let area = Rect::new(0, 0, GRID_LABEL_WIDTH + 4 * GRID_CELL_WIDTH, GRID_HEIGHT);
+        assert_eq!(
+            grid.get_date_at(area, GRID_LABEL_WIDTH + 1, 3),
+            NaiveDate::from_ymd_opt(2026, 9, 22)
+        );
+        assert_eq!(grid.get_date_at(area, 1, 3), None);
+        assert_eq!(grid.get_date_at(area, GRID_LABEL_WIDTH, 1), None);
+        assert_eq!(grid.move_date(today, 7), today);
+
+        // Wednesday, the rest of the week is not shown
+        let grid = GridActivity::new(200, NaiveDate::from_ymd_opt(2026, 10, 14).unwrap());
+
+        assert_eq!(grid.weeks, 52);
+        assert!(grid.get_date(51, 2).is_some());
+        assert_eq!(grid.get_date(51, 3), None);
+    }
+
+    #[test]
+    fn filters_cycle_through_the_tags_and_the_exercises() {
+        let get_task = |title: &str, status: u8| Task {
+            title: title.to_string(),
+            status: Status::new(status),
+            priority: 0,
+            target: 0.0,
+            progress: 0.0,
+            sets: vec![],
+            weight: 0.0,
+            kind: TaskKind::Reps,
+            track: None,
+        };
+        let project = Project {
+            title: "18.10.2026".to_string(),
+            tasks: vec![get_task("squats", 100), get_task("pushups", 0)],
+            day_off: None,
+        };
+        let projects = vec![project];
+        let tags = BTreeMap::from([("legs".to_string(), BTreeSet::from(["Squats".to_string()]))]);
+
+        let filters = GridFilter::get_all(&projects, tags.clone());
+        let titles: Vec<String> = filters.iter().map(|f| f.get_title()).collect();
+
+        assert_eq!(titles, ["all exercises", "#legs", "pushups", "squats"]);
+        assert_eq!(filters[3].next(&projects, tags), GridFilter::All);
+
+        assert_eq!(
+            GridActivity::convert_project_to_activityf32(&projects[0], &filters[0]),
+            50.0
+        );
+        assert_eq!(
+            GridActivity::convert_project_to_activityf32(&projects[0], &filters[1]),
+            100.0
+        );
+        assert_eq!(
+            GridActivity::get_tooltip(&projects, today(), &filters[2]),
+            "Sun 18.10.2026: 0/1 done (0%), pushups"
+        );
     }
 }
@@ -1,9 +1,19 @@
-use crate::{project::Project, task::Task, ui::Ui, util::Util, App, ViewMode};
-use grid_activity::{GridBlock, GridBlockConf};
+use crate::{
+    keys::Action,
+    project::Project,
+    task::{Task, TaskKind},
+    ui::Ui,
+    util::Util,
+    App, ViewMode,
+};
+use chrono::Local;
+use grid_activity::{
+    GridActivity, GridCell, GRID_BLOCK, GRID_CELL_WIDTH, GRID_HEIGHT, GRID_LABEL_WIDTH,
+};
 use ratatui::{
     layout::{Alignment, Rect},
-    style::{Color, Modifier, Style},
-    text::{Line, Text},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
     widgets::{Block, Clear, HighlightSpacing, List, ListItem, Paragraph, Wrap},
     Frame,
 };
@@ -29,10 +39,39 @@ impl View {
         Ui::create_modal(f, 30, 4, area, widget)
     }
 
+    pub fn show_read_only_info_modal(f: &mut Frame, area: Rect) {
+        let widget = Paragraph::new(Text::from(vec![
+            Line::raw("Another session is running!"),
+            Line::raw("Changes won't be saved"),
+        ]))
+        .alignment(Alignment::Center)
+        .block(Block::bordered());
+
+        Ui::create_modal(f, 30, 4, area, widget)
+    }
+
+    pub fn show_conflict_modal(f: &mut Frame, area: Rect) {
+        Ui::create_question_modal(
+            "The data was changed outside!",
+            "Overwrite it or reload?",
+            "Conflict",
+            f,
+            area,
+        )
+    }
+
     pub fn show_rename_item_modal(f: &mut Frame, area: Rect, input: &Input) {
         Ui::create_input_modal("Rename", f, area, input)
     }
 
+    pub fn show_sets_task_modal(f: &mut Frame, area: Rect, input: &Input, kind: TaskKind) {
+        Ui::create_input_modal(kind.get_input_title(), f, area, input)
+    }
+
+    pub fn show_day_off_modal(f: &mut Frame, area: Rect, input: &Input) {
+        Ui::create_input_modal("Day off (rest|sick|travel: note)", f, area, input)
+    }
+
     pub fn show_delete_item_modal(app: &mut App, f: &mut Frame, area: Rect) {
         let title = match app.view_mode {
             ViewMode::DeleteTask => &Task::get_current(app).title,
@@ -90,6 +129,8 @@ impl View {
             ViewMode::ViewProjects
             | ViewMode::AddProject
             | ViewMode::RenameProject
+            | ViewMode::ChangeDayOffProject
+            | ViewMode::ViewGrid
             | ViewMode::DeleteProject => Block::bordered(),
             _ => Block::bordered().title(Util::get_spaced_title(&Project::get_current(app).title)),
         };
@@ -114,135 +155,168 @@ impl View {
     }
 
     pub fn show_grid_activity(app: &mut App, f: &mut Frame, area: Rect) {
-        let colors: Vec<Color> = app
-            .projects
-            .iter()
-            .map(|project| {
-                let activity_f32 =
-                    grid_activity::GridActivity::convert_project_to_activityf32(project);
+        let grid = GridActivity::new(area.width, Local::now().date_naive());
+        let cells = GridActivity::get_cells(&app.projects, &app.grid_filter);
+        let theme = &app.theme;
+
+        // Block and style of a cell
+        let get_block = |cell: GridCell| match cell {
+            GridCell::Activity(level) => (
+                theme.get_heatmap_block(level, GRID_BLOCK),
+                Style::default().fg(theme.heatmap[level.min(4)]),
+            ),
+            GridCell::DayOff => (
+                theme.get_day_off_block(GRID_BLOCK),
+                Style::default().fg(theme.day_off),
+            ),
+        };
 
-                let activity_i32 = activity_f32 as i32; // Cast `f32` to `i32`
-                grid_activity::GridActivity::convert_activityi32_to_color(&activity_i32)
-            })
-            .collect();
+        // The cursor is shown only while the grid is browsed
+        let selected_date = app
+            .selected_grid_date
+            .filter(|_| app.view_mode == ViewMode::ViewGrid);
 
-        // Define the grid block configuration
-        let grid_block_conf = GridBlockConf::new(
-            2,
-            2,
-            char::from_u32(0x00002588) // ASCII block
-                .map(|c| c.to_string())
-                .unwrap_or_else(|| "?".to_string()),
+        let mut lines = Vec::with_capacity(GRID_HEIGHT as usize);
+
+        lines.push(
+            Line::raw(format!("Activity: {}", app.grid_filter.get_title()))
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .alignment(Alignment::Center),
         );
 
-        let rows_n = 6;
-        let cols_n = (colors.len() as f32 / rows_n as f32).ceil() as usize;
+        // Month labels, placed above their week
+        let mut months = " ".repeat(GRID_LABEL_WIDTH as usize);
+        for (week, label) in grid.get_month_labels() {
+            let column = (GRID_LABEL_WIDTH + week * GRID_CELL_WIDTH) as usize;
+            months.push_str(&" ".repeat(column.saturating_sub(months.chars().count())));
+            months.push_str(&label);
+        }
+        lines.push(Line::raw(months).style(Style::default().fg(theme.muted)));
+
+        for (weekday, label) in ["Mon", "", "Wed", "", "Fri", "", ""].iter().enumerate() {
+            let mut spans = vec![Span::raw(format!(
+                "{label:<width$}",
+                width = GRID_LABEL_WIDTH as usize
+            ))
+            .style(Style::default().fg(theme.muted))];
+
+            for week in 0..grid.weeks {
+                // The days after today are left blank
+                let Some(date) = grid.get_date(week, weekday as u16) else {
+                    break;
+                };
 
-        let mut blocks: Vec<Vec<GridBlock>> = Vec::with_capacity(rows_n);
+                let (block, mut style) =
+                    get_block(cells.get(&date).copied().unwrap_or(GridCell::Activity(0)));
 
-        for row in 0..rows_n {
-            let mut row_blocks = Vec::with_capacity(cols_n);
-            for col in 0..cols_n {
-                let index = col * rows_n + row;
-                if index < colors.len() {
-                    row_blocks.push(GridBlock::new(colors[index]));
+                if selected_date == Some(date) {
+                    style = style.add_modifier(Modifier::REVERSED);
                 }
-            }
-            blocks.push(row_blocks);
-        }
-
-        let grid = grid_activity::GridActivity::new(1, 0, 1, grid_block_conf, blocks);
 
-        let total_rows = grid.blocks.len() as u16;
-        let total_cols = grid.blocks[0].len() as u16;
+                spans.push(Span::raw(block).style(style));
+                spans.push(Span::raw(" "));
+            }
 
-        if total_cols == 0 || total_rows == 0 {
-            eprintln!("total_rows or total_cols == 0!");
-            return;
+            lines.push(Line::from(spans));
         }
 
-        // Calculate the total spacing
-        let total_col_spacing = (total_cols - 1) * grid.col_spacing;
-        let total_row_spacing = grid.row_spacing;
-
-        if total_col_spacing >= area.width || total_row_spacing >= area.height {
-            eprintln!(
-                "Not enough space to render the grid. Adjust spacing or increase area size {} {}.",
-                total_cols, total_rows
-            );
-            return;
+        // Legend, from no activity to every exercise done
+        let mut legend = vec![Span::raw("Less ").style(Style::default().fg(theme.muted))];
+        for level in 0..5 {
+            let (block, style) = get_block(GridCell::Activity(level));
+            legend.push(Span::raw(block).style(style));
+            legend.push(Span::raw(" "));
         }
-
-        // Calculate the size of each block
-        let block_width = grid.block_conf.width;
-        let block_height = grid.block_conf.height;
-
-        if block_width == 0 || block_height == 0 {
-            eprintln!("Block size is too small. Adjust spacing or increase area size.");
-            return;
+        legend.push(Span::raw("More  ").style(Style::default().fg(theme.muted)));
+        let (block, style) = get_block(GridCell::DayOff);
+        legend.push(Span::raw(block).style(style));
+        legend.push(Span::raw(" Day off").style(Style::default().fg(theme.muted)));
+        lines.push(Line::from(legend).alignment(Alignment::Right));
+
+        if let Some(date) = selected_date {
+            lines.push(Line::raw(GridActivity::get_tooltip(
+                &app.projects,
+                date,
+                &app.grid_filter,
+            )));
         }
 
-        // Render each block in the grid
-        for (row_index, row) in grid.blocks.iter().enumerate() {
-            for (col_index, block) in row.iter().enumerate() {
-                let block_x = grid.start_offset
-                    + area.x
-                    + col_index as u16 * (block_width + grid.col_spacing);
-                let block_y = area.y + row_index as u16 * (block_height + grid.row_spacing);
-
-                // Calculate the position of the block
-                let block_area = Rect {
-                    x: grid.start_offset
-                        + area.x
-                        + col_index as u16 * (block_width + grid.col_spacing),
-                    y: area.y + row_index as u16 * (block_height + grid.row_spacing),
-                    width: block_width,
-                    height: block_height,
-                };
-
-                let paragraph = Paragraph::new(
-                    grid.block_conf
-                        .view
-                        .repeat(grid.block_conf.width as usize)
-                        .clone(),
-                )
-                .style(block.color)
-                .wrap(Wrap { trim: false })
-                .centered();
-
-                // make sure widget can be placed inside the window
-                if !(block_x >= area.x + area.width || block_y >= area.y + area.height) {
-                    f.render_widget(paragraph, block_area);
-                }
-            }
-        }
+        f.render_widget(Paragraph::new(lines), grid.get_area(area));
     }
 
     pub fn show_footer_helper(app: &mut App, f: &mut Frame, area: Rect) {
+        // The keys come from the config, except the <Enter> and <Esc> of the text editors
+        if app.show_keys {
+            return View::show_footer_text("<any key> close", f, area);
+        }
+
         let help_string = match app.view_mode {
-            ViewMode::ViewProjects => {
-                "<k/j> next/prev :: <l> go to tasks :: <a/n> new :: <r> rename :: <d> delete :: <q> quit"
-            }
+            ViewMode::ViewProjects
+            | ViewMode::ViewTasks
+            | ViewMode::ViewGrid
+            | ViewMode::DeleteProject
+            | ViewMode::DeleteTask
+            | ViewMode::ConflictExternalChange => &app.keys.get_footer(app.view_mode, |_| true),
+            ViewMode::ChangeStatusTask | ViewMode::ChangePriorityTask => &format!(
+                "{} :: <Enter> confirm :: <Esc> cancel",
+                app.keys.get_footer(ViewMode::ViewTasks, |a| {
+                    matches!(a, Action::Next | Action::Previous)
+                })
+            ),
             ViewMode::RenameProject => "<Enter> confirm :: <Esc> cancel",
+            ViewMode::ChangeDayOffProject => "<Enter> confirm (empty to clear) :: <Esc> cancel",
             ViewMode::AddProject => "<Enter> confirm :: <Esc> cancel",
-            ViewMode::DeleteProject => "<y> confirm :: <n> cancel",
 
-            ViewMode::ViewTasks => {
-                "<k/j> next/prev :: <h> go to projects :: <Enter> change status :: <p> change priority :: <a/n> new :: <r> rename :: <d> delete :: <q> quit"
-            }
             ViewMode::RenameTask => "<Enter> confirm :: <Esc> cancel",
-            ViewMode::ChangeStatusTask => "<k/j> next/prev :: <Enter> confirm :: <Esc> cancel",
-            ViewMode::ChangePriorityTask => "<k/j> next/prev :: <Enter> confirm :: <Esc> cancel",
+            ViewMode::ChangeSetsTask => "<Enter> confirm :: <Esc> cancel",
             ViewMode::AddTask => "<Enter> confirm :: <Esc> cancel",
-            ViewMode::DeleteTask => "<y> confirm :: <n> cancel",
-            ViewMode::InfoMigration => ""
+            ViewMode::InfoMigration => "",
+            ViewMode::InfoReadOnly => "",
         };
 
+        View::show_footer_text(help_string, f, area)
+    }
+
+    fn show_footer_text(text: &str, f: &mut Frame, area: Rect) {
         f.render_widget(
-            Paragraph::new(help_string)
+            Paragraph::new(text)
                 .wrap(Wrap { trim: true })
                 .alignment(Alignment::Center),
             area,
         );
     }
+
+    // Every key of the actions of the view, the footer helper shows only the first ones
+    pub fn show_keys_modal(app: &mut App, f: &mut Frame, area: Rect) {
+        let bindings = app.keys.get_bindings(app.view_mode);
+
+        let lines: Vec<(String, &str)> = bindings
+            .iter()
+            .filter(|b| !b.keys.is_empty())
+            .map(|b| {
+                let keys: Vec<String> = b.keys.iter().map(|k| k.to_string()).collect();
+                (keys.join("/"), b.description)
+            })
+            .collect();
+
+        let width = lines.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+
+        let widget = Paragraph::new(Text::from(
+            lines
+                .iter()
+                .map(|(keys, description)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!(" {keys:>width$}  "),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(*description),
+                    ])
+                })
+                .collect::<Vec<Line>>(),
+        ))
+        .block(Block::bordered().title("Keys"));
+
+        Ui::create_modal(f, 40, lines.len() as u16 + 2, area, widget)
+    }
 }